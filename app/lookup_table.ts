@@ -0,0 +1,100 @@
+import {
+  AccountMeta,
+  AddressLookupTableAccount,
+  AddressLookupTableProgram,
+  Commitment,
+  Connection,
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+  TransactionMessage,
+  VersionedTransaction,
+} from "@solana/web3.js";
+
+// a legacy transaction only fits about 20 new addresses per extend
+const EXTEND_CHUNK = 20;
+
+const confirm = async (connection: Connection, signature: string, commitment: Commitment) => {
+  const latestBlockhash = await connection.getLatestBlockhash();
+  await connection.confirmTransaction({ signature, ...latestBlockhash }, commitment);
+};
+
+// [mint, token_account, metadata, edition, stake_account], the remaining accounts of one NFT in
+// `stakeNftBatch`/`unstakeNftBatch`
+export const batchNftAccounts = (
+  mint: PublicKey,
+  tokenAccount: PublicKey,
+  metadata: PublicKey,
+  edition: PublicKey,
+  stakeAccount: PublicKey
+): AccountMeta[] => [
+  { pubkey: mint, isSigner: false, isWritable: false },
+  { pubkey: tokenAccount, isSigner: false, isWritable: true },
+  { pubkey: metadata, isSigner: false, isWritable: false },
+  { pubkey: edition, isSigner: false, isWritable: false },
+  { pubkey: stakeAccount, isSigner: false, isWritable: true },
+];
+
+// Puts the accounts shared by every NFT of a batch (and the per NFT ones) in an Address Lookup
+// Table, so a `stakeNftBatch`/`unstakeNftBatch` of up to MAX_NFT_BATCH NFTs fits in one v0 transaction
+export const createBatchLookupTable = async (
+  connection: Connection,
+  authority: Keypair,
+  addresses: PublicKey[],
+  commitment: Commitment = "confirmed"
+): Promise<AddressLookupTableAccount> => {
+  const [createIx, lookupTable] = AddressLookupTableProgram.createLookupTable({
+    authority: authority.publicKey,
+    payer: authority.publicKey,
+    recentSlot: await connection.getSlot("finalized"),
+  });
+
+  const ixs: TransactionInstruction[] = [createIx];
+  for (let i = 0; i < addresses.length; i += EXTEND_CHUNK) {
+    ixs.push(
+      AddressLookupTableProgram.extendLookupTable({
+        authority: authority.publicKey,
+        payer: authority.publicKey,
+        lookupTable,
+        addresses: addresses.slice(i, i + EXTEND_CHUNK),
+      })
+    );
+  }
+
+  for (const ix of ixs) {
+    const sig = await connection.sendTransaction(new Transaction().add(ix), [authority]);
+    await confirm(connection, sig, commitment);
+  }
+
+  // a table can only be used from the slot after it was last extended
+  const extendedAt = await connection.getSlot(commitment);
+  while ((await connection.getSlot(commitment)) <= extendedAt) {
+    await new Promise((resolve) => setTimeout(resolve, 200));
+  }
+
+  return (await connection.getAddressLookupTable(lookupTable, { commitment })).value;
+};
+
+// signs and sends `ixs` as a v0 transaction resolving accounts through `lookupTables`
+export const sendV0 = async (
+  connection: Connection,
+  ixs: TransactionInstruction[],
+  signer: Keypair,
+  lookupTables: AddressLookupTableAccount[],
+  commitment: Commitment = "confirmed"
+): Promise<string> => {
+  const { blockhash } = await connection.getLatestBlockhash();
+  const message = new TransactionMessage({
+    payerKey: signer.publicKey,
+    recentBlockhash: blockhash,
+    instructions: ixs,
+  }).compileToV0Message(lookupTables);
+
+  const tx = new VersionedTransaction(message);
+  tx.sign([signer]);
+
+  const sig = await connection.sendTransaction(tx);
+  await confirm(connection, sig, commitment);
+  return sig;
+};
@@ -0,0 +1,207 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        pubkey,
+    },
+};
+
+pub const BUBBLEGUM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+pub const SPL_ACCOUNT_COMPRESSION_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+pub const SPL_NOOP_ID: Pubkey = pubkey!("noopb9bkMVfRPU8ShW8kYWqqJ7kfnMY5bWvdxZKc4fi");
+
+// anchor discriminator of Bubblegum's `transfer`
+const TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+// Bubblegum's `MetadataArgs`. The leaf only stores its hash, so the staker passes it in full and
+// the collection is trusted only because the transfer fails unless the hash matches the leaf.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<TokenStandard>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+    pub token_program_version: TokenProgramVersion,
+    pub creators: Vec<Creator>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum TokenStandard {
+    NonFungible,
+    FungibleAsset,
+    Fungible,
+    NonFungibleEdition,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum UseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Uses {
+    pub use_method: UseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum TokenProgramVersion {
+    Original,
+    Token2022,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+pub fn get_asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()], &BUBBLEGUM_ID).0
+}
+
+// same hashing as Bubblegum's `hash_metadata`
+pub fn data_hash(metadata: &MetadataArgs) -> Result<[u8; 32]> {
+    let metadata_hash = solana_keccak_hasher::hash(&metadata.try_to_vec()?);
+
+    Ok(solana_keccak_hasher::hashv(&[
+        metadata_hash.as_ref(),
+        &metadata.seller_fee_basis_points.to_le_bytes(),
+    ]).to_bytes())
+}
+
+// same hashing as Bubblegum's `hash_creators`
+pub fn creator_hash(creators: &[Creator]) -> [u8; 32] {
+    let creator_data: Vec<[u8; 34]> = creators
+        .iter()
+        .map(|creator| {
+            let mut bytes = [0u8; 34];
+            bytes[..32].copy_from_slice(creator.address.as_ref());
+            bytes[32] = u8::from(creator.verified);
+            bytes[33] = creator.share;
+            bytes
+        })
+        .collect();
+
+    let slices: Vec<&[u8]> = creator_data.iter().map(|bytes| bytes.as_ref()).collect();
+
+    solana_keccak_hasher::hashv(&slices).to_bytes()
+}
+
+// The accounts of a Bubblegum `transfer`, the merkle proof nodes come from `remaining_accounts`.
+pub struct LeafTransfer<'a, 'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub leaf_delegate: AccountInfo<'info>,
+    pub new_leaf_owner: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub bubblegum_program: AccountInfo<'info>,
+    pub proof: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> LeafTransfer<'a, 'info> {
+    pub fn transfer(self, root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32], index: u32, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        // leaves minted by Bubblegum v1 use their index as nonce
+        let nonce = u64::from(index);
+
+        let mut data = TRANSFER_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&root);
+        data.extend_from_slice(&data_hash);
+        data.extend_from_slice(&creator_hash);
+        data.extend_from_slice(&nonce.to_le_bytes());
+        data.extend_from_slice(&index.to_le_bytes());
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.tree_config.key(), false),
+            AccountMeta::new_readonly(self.leaf_owner.key(), true),
+            AccountMeta::new_readonly(self.leaf_delegate.key(), false),
+            AccountMeta::new_readonly(self.new_leaf_owner.key(), false),
+            AccountMeta::new(self.merkle_tree.key(), false),
+            AccountMeta::new_readonly(self.log_wrapper.key(), false),
+            AccountMeta::new_readonly(self.compression_program.key(), false),
+            AccountMeta::new_readonly(self.system_program.key(), false),
+        ];
+        accounts.extend(self.proof.iter().map(|node| AccountMeta::new_readonly(node.key(), false)));
+
+        let mut account_infos = vec![
+            self.tree_config,
+            self.leaf_owner,
+            self.leaf_delegate,
+            self.new_leaf_owner,
+            self.merkle_tree,
+            self.log_wrapper,
+            self.compression_program,
+            self.system_program,
+            self.bubblegum_program,
+        ];
+        account_infos.extend_from_slice(self.proof);
+
+        let instruction = Instruction {
+            program_id: BUBBLEGUM_ID,
+            accounts,
+            data,
+        };
+
+        invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(collection: Pubkey) -> MetadataArgs {
+        MetadataArgs {
+            name: "Stacker #1".to_string(),
+            symbol: "STK".to_string(),
+            uri: "https://example.com/1.json".to_string(),
+            seller_fee_basis_points: 500,
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: None,
+            token_standard: Some(TokenStandard::NonFungible),
+            collection: Some(Collection { verified: true, key: collection }),
+            uses: None,
+            token_program_version: TokenProgramVersion::Original,
+            creators: vec![],
+        }
+    }
+
+    #[test]
+    fn data_hash_commits_to_the_collection() {
+        let collection = Pubkey::new_unique();
+
+        let real = data_hash(&metadata(collection)).unwrap();
+        let forged = data_hash(&metadata(Pubkey::new_unique())).unwrap();
+
+        assert_eq!(real, data_hash(&metadata(collection)).unwrap());
+        assert_ne!(real, forged);
+    }
+
+    #[test]
+    fn creator_hash_of_no_creators_is_the_empty_hash() {
+        assert_eq!(creator_hash(&[]), solana_keccak_hasher::hash(&[]).to_bytes());
+    }
+}
@@ -3,5 +3,35 @@ use anchor_lang::prelude::*;
 #[constant]
 pub const SEED: &str = "anchor";
 
+// only used to gate `initialize_config`, afterwards the admin stored in `StateConfig` is checked
 pub const ADMIN: Pubkey = pubkey!("DKbqMnDju2ftYBKM65DhPMLi7foVt5QPmbCmeeTk5eSN");
 
+
+pub const MAX_ANNUAL_PERCENTAGE_RATE: u16 = 10_000; // 100% in basis points
+
+pub const MAX_EARLY_UNSTAKE_PENALTY_BPS: u16 = 10_000; // the whole principal
+
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+pub const PAUSE_SOL: u8 = 1 << 0;
+pub const PAUSE_SPL: u8 = 1 << 1;
+pub const PAUSE_NFT: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_SOL | PAUSE_SPL | PAUSE_NFT;
+
+pub const MAX_LOCK_TIERS: usize = 8;
+
+pub const SOL_STAKE_BONUS: u64 = 100_000_000; // one time reward for opening a SOL position
+
+pub const SPL_STAKE_BONUS: u64 = 100_000_000; // one time reward for opening an SPL position, flat so it can't be farmed with size
+
+pub const NFT_STAKE_BONUS: u64 = 100_000_000; // one time reward per staked NFT, batches included
+
+pub const NFT_YIELD_PRINCIPAL: u64 = 1_000_000_000; // principal one NFT counts as for the locked-staker APR yield
+
+pub const MAX_NFT_BATCH: usize = 10; // bounded by the compute budget of one freeze/thaw per NFT
+
+pub const MAX_LOCK_MULTIPLIER_BPS: u16 = 50_000; // 5x
+
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
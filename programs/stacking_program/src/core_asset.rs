@@ -0,0 +1,141 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::{invoke, invoke_signed},
+        pubkey,
+    },
+};
+
+use crate::error::ErrorCode;
+
+pub const MPL_CORE_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+// Metaplex Core instruction and enum indexes, encoded by hand since only three instructions are needed
+const ADD_PLUGIN_V1: u8 = 2;
+const REMOVE_PLUGIN_V1: u8 = 4;
+const UPDATE_PLUGIN_V1: u8 = 6;
+const FREEZE_DELEGATE: u8 = 1; // `Plugin` and `PluginType` variant
+const AUTHORITY_ADDRESS: u8 = 3; // `PluginAuthority::Address`
+
+const KEY_ASSET_V1: u8 = 1;
+const UPDATE_AUTHORITY_COLLECTION: u8 = 2;
+
+// Reads the owner and collection from the head of a `BaseAssetV1`:
+// key (u8), owner (Pubkey), update_authority (enum tag + Pubkey), then name, uri and plugins.
+pub fn asset_owner_and_collection(data: &[u8]) -> Result<(Pubkey, Option<Pubkey>)> {
+    require!(data.len() >= 66 && data[0] == KEY_ASSET_V1, ErrorCode::InvalidCoreAsset);
+
+    let owner = Pubkey::try_from(&data[1..33]).or(Err(ErrorCode::InvalidCoreAsset))?;
+
+    let collection = match data[33] {
+        UPDATE_AUTHORITY_COLLECTION => Some(Pubkey::try_from(&data[34..66]).or(Err(ErrorCode::InvalidCoreAsset))?),
+        _ => None,
+    };
+
+    Ok((owner, collection))
+}
+
+// Freezes a Core asset in the owner's wallet with a Freeze Delegate plugin whose authority is the
+// stake account, and removes the plugin again on unstake so the asset can be staked later.
+pub struct CoreFreeze<'info> {
+    pub owner: AccountInfo<'info>,
+    // the stake account PDA, authority of the freeze delegate while staked
+    pub delegate: AccountInfo<'info>,
+    pub asset: AccountInfo<'info>,
+    pub collection: AccountInfo<'info>,
+    pub core_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> CoreFreeze<'info> {
+    pub fn freeze(&self) -> Result<()> {
+        let mut data = vec![ADD_PLUGIN_V1, FREEZE_DELEGATE, 1, 1, AUTHORITY_ADDRESS];
+        data.extend_from_slice(self.delegate.key.as_ref());
+
+        invoke(&self.instruction(&self.owner, data), &self.account_infos(&self.owner))?;
+
+        Ok(())
+    }
+
+    pub fn thaw(&self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let data = vec![UPDATE_PLUGIN_V1, FREEZE_DELEGATE, 0];
+        invoke_signed(&self.instruction(&self.delegate, data), &self.account_infos(&self.delegate), signer_seeds)?;
+
+        // the owner manages the plugin, once unfrozen it can take it off
+        let data = vec![REMOVE_PLUGIN_V1, FREEZE_DELEGATE];
+        invoke(&self.instruction(&self.owner, data), &self.account_infos(&self.owner))?;
+
+        Ok(())
+    }
+
+    // accounts shared by AddPluginV1, UpdatePluginV1 and RemovePluginV1,
+    // the optional log wrapper is left out by passing the program id
+    fn instruction(&self, authority: &AccountInfo<'info>, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: MPL_CORE_ID,
+            accounts: vec![
+                AccountMeta::new(self.asset.key(), false),
+                AccountMeta::new(self.collection.key(), false),
+                AccountMeta::new(self.owner.key(), true),
+                AccountMeta::new_readonly(authority.key(), true),
+                AccountMeta::new_readonly(self.system_program.key(), false),
+                AccountMeta::new_readonly(MPL_CORE_ID, false),
+            ],
+            data,
+        }
+    }
+
+    fn account_infos(&self, authority: &AccountInfo<'info>) -> Vec<AccountInfo<'info>> {
+        vec![
+            self.asset.clone(),
+            self.collection.clone(),
+            self.owner.clone(),
+            authority.clone(),
+            self.system_program.clone(),
+            self.core_program.clone(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(owner: Pubkey, update_authority: u8, collection: Pubkey) -> Vec<u8> {
+        let mut data = vec![KEY_ASSET_V1];
+        data.extend_from_slice(owner.as_ref());
+        data.push(update_authority);
+        data.extend_from_slice(collection.as_ref());
+        data.extend_from_slice(&[0; 8]); // empty name and uri
+        data
+    }
+
+    #[test]
+    fn reads_owner_and_collection() {
+        let owner = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+
+        let parsed = asset_owner_and_collection(&asset(owner, UPDATE_AUTHORITY_COLLECTION, collection)).unwrap();
+
+        assert_eq!(parsed, (owner, Some(collection)));
+    }
+
+    #[test]
+    fn asset_without_collection_has_none() {
+        let owner = Pubkey::new_unique();
+
+        let parsed = asset_owner_and_collection(&asset(owner, 1, Pubkey::new_unique())).unwrap();
+
+        assert_eq!(parsed, (owner, None));
+    }
+
+    #[test]
+    fn rejects_non_asset_accounts() {
+        let mut data = asset(Pubkey::new_unique(), UPDATE_AUTHORITY_COLLECTION, Pubkey::new_unique());
+        data[0] = 5; // CollectionV1
+
+        assert!(asset_owner_and_collection(&data).is_err());
+        assert!(asset_owner_and_collection(&data[..40]).is_err());
+    }
+}
@@ -17,4 +17,118 @@ pub enum ErrorCode {
     #[msg("Stake Periode is too Low")]
     TooLessStakePeriod,
 
+    #[msg("Freeze Periode can not be negative")]
+    InvalidFreezePeriod,
+
+    #[msg("Annual Percentage Rate is too High")]
+    AprTooHigh,
+
+    #[msg("Invalid pending admin")]
+    InvalidPendingAdmin,
+
+    #[msg("Stake Account does not belong to user")]
+    InvalidStakeOwner,
+
+    #[msg("Staking is Paused")]
+    StakingPaused,
+
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("Pool is Disabled")]
+    PoolDisabled,
+
+    #[msg("Pool Account is missing")]
+    MissingPool,
+
+    #[msg("Min Stake Amount is greater than Max Stake Amount")]
+    InvalidStakeLimits,
+
+    #[msg("Stake Amount is too Low")]
+    StakeAmountTooLow,
+
+    #[msg("Stake Amount is too High")]
+    StakeAmountTooHigh,
+
+    #[msg("Collection is not Allowed")]
+    CollectionNotAllowed,
+
+    #[msg("Reward accounts do not match the Reward Mode")]
+    InvalidRewardAccounts,
+
+    #[msg("Reward Vault is missing")]
+    MissingRewardVault,
+
+    #[msg("Instruction not available in this Reward Mode")]
+    InvalidRewardMode,
+
+    #[msg("Insufficient Reward Funds in vault")]
+    InsufficientRewardFunds,
+
+    #[msg("Total Emission Cap Exceeded")]
+    EmissionCapExceeded,
+
+    #[msg("Epoch Emission Budget Exceeded")]
+    EpochBudgetExceeded,
+
+    #[msg("Invalid Lock Tiers")]
+    InvalidLockTiers,
+
+    #[msg("Early Unstake Penalty Too High")]
+    PenaltyTooHigh,
+
+    #[msg("Invalid Unstake Amount")]
+    InvalidUnstakeAmount,
+
+    #[msg("Compounding Not Allowed For This Position")]
+    CompoundingNotAllowed,
+
+    #[msg("Compounding Disabled")]
+    CompoundingDisabled,
+
+    #[msg("Invalid Asset Kind")]
+    InvalidAssetKind,
+
+    #[msg("Validator Not Set")]
+    ValidatorNotSet,
+
+    #[msg("Stake Is Delegated")]
+    StakeIsDelegated,
+
+    #[msg("Stake Not Delegated")]
+    StakeNotDelegated,
+
+    #[msg("Native Stake Not Deactivated")]
+    StakeNotDeactivated,
+
+    #[msg("Deactivate The Native Stake Before Unstaking")]
+    StakeDeactivationRequired,
+
+    #[msg("Missing Native Stake Accounts")]
+    MissingNativeStakeAccounts,
+
+    #[msg("Missing Programmable Nft Accounts")]
+    MissingProgrammableAccounts,
+
+    #[msg("Invalid Core Asset")]
+    InvalidCoreAsset,
+
+    #[msg("Invalid Compressed Nft")]
+    InvalidCompressedNft,
+
+    #[msg("Missing Nft Vault")]
+    MissingNftVault,
+
+    #[msg("Nft Vault Is Only Used For Escrowed Nfts")]
+    UnexpectedNftVault,
+
+    #[msg("Mint Is Not An Nft")]
+    NotAnNft,
+
+    #[msg("Invalid Nft Batch")]
+    InvalidNftBatch,
+
+    #[msg("Insufficient Treasury Funds")]
+    InsufficientTreasuryFunds,
+
 }
@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+
+use crate::{AssetKind, LockTier};
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub old_sol_reward_rate: u64,
+    pub new_sol_reward_rate: u64,
+    pub old_min_freeze_period: i64,
+    pub new_min_freeze_period: i64,
+    pub old_annaul_percentage_rate: u16,
+    pub new_annaul_percentage_rate: u16,
+    pub old_max_total_emission: u64,
+    pub new_max_total_emission: u64,
+    pub old_epoch_emission_budget: u64,
+    pub new_epoch_emission_budget: u64,
+    pub old_early_unstake_penalty_bps: u16,
+    pub new_early_unstake_penalty_bps: u16,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub admin: Pubkey,
+    pub old_paused: u8,
+    pub new_paused: u8,
+}
+
+#[event]
+pub struct LockTiersUpdated {
+    pub admin: Pubkey,
+    pub tiers: Vec<LockTier>,
+}
+
+#[event]
+pub struct SplPoolUpdated {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub reward_rate: u64,
+    pub min_stake_amount: u64,
+    pub max_stake_amount: u64,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct NftPoolUpdated {
+    pub admin: Pubkey,
+    pub collection_mint: Pubkey,
+    pub reward_rate: u64,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub admin: Pubkey,
+    pub mint: Option<Pubkey>, // None for lamports
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub asset_kind: AssetKind,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub seed: u64,
+    pub lock_period: i64,
+    pub locked_stackers: bool,
+    pub staked_at: i64,
+}
+
+#[event]
+pub struct StakeIncreased {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub asset_kind: AssetKind,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub staked_amt: u64,
+    pub lock_period: i64,
+    pub staked_at: i64,
+}
+
+#[event]
+pub struct Compounded {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub amount: u64,
+    pub staked_amt: u64,
+    pub compounded_at: i64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub asset_kind: AssetKind,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub seed: u64,
+    pub lock_period: i64,
+    pub staked_at: i64,
+    pub unstaked_at: i64,
+    pub remaining: u64, // still staked in the position, it is closed once this reaches zero
+    pub rewards_forfeited: bool,
+}
+
+#[event]
+pub struct RewardPaid {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+    pub paid_at: i64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub amount: u64,
+    pub claimed_from: i64, // previous checkpoint, the claim covers `claimed_from..claimed_at`
+    pub claimed_at: i64,
+}
+
+#[event]
+pub struct EmergencyUnstaked {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub asset_kind: AssetKind,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub seed: u64,
+    pub staked_at: i64,
+    pub unstaked_at: i64,
+}
+
+#[event]
+pub struct ValidatorUpdated {
+    pub admin: Pubkey,
+    pub vote_account: Option<Pubkey>,
+}
+
+#[event]
+pub struct StakeDelegated {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub vote_account: Pubkey,
+    pub amount: u64,
+    pub activation_epoch: u64,
+}
+
+#[event]
+pub struct StakeDeactivated {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub deactivation_epoch: u64,
+}
+
+#[event]
+pub struct NativeStakeWithdrawn {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub principal: u64,
+    pub native_yield: u64,
+    pub shortfall: u64, // principal lost to slashing, it never made it back into the vault
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::AdminTransferred, StateConfig};
+
+#[derive(Accounts)]
+pub struct AcceptAdmin <'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = config.pending_admin == Some(new_admin.key()) @ ErrorCode::InvalidPendingAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+}
+
+impl <'info> AcceptAdmin <'info> {
+    pub fn accept_admin(&mut self) -> Result<()> {
+        let old_admin = self.config.admin;
+
+        self.config.admin = self.new_admin.key();
+        self.config.pending_admin = None;
+
+        emit!(AdminTransferred {
+            old_admin,
+            new_admin: self.new_admin.key(),
+        });
+
+        Ok(())
+    }
+}
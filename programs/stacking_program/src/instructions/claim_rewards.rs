@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::Token, token_interface::{Mint, TokenAccount}};
+
+use crate::{error::ErrorCode, events::RewardsClaimed, rewards::RewardPayout, AssetKind, NftCollectionPool, SplPool, StakeAccount, StateConfig, UserAccount};
+
+#[derive(Accounts)]
+pub struct ClaimRewards <'info> {
+
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = stake_account.owner == user.key() @ ErrorCode::InvalidStakeOwner,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    // required when claiming on an SPL position
+    #[account(
+        mut,
+        seeds = [b"pool", config.key().as_ref(), stake_account.mint.as_ref()],
+        bump = spl_pool.bump,
+    )]
+    pub spl_pool: Option<Account<'info, SplPool>>,
+
+    // required when claiming on an NFT position
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), stake_account.collection.as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Option<Account<'info, NftCollectionPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl <'info> ClaimRewards <'info> {
+    pub fn claim_rewards(&mut self) -> Result<()> {
+
+        require!(!self.config.is_paused(self.stake_account.asset_kind), ErrorCode::StakingPaused);
+
+        let current = Clock::get()?.unix_timestamp;
+
+        let accumulator = match self.stake_account.asset_kind {
+            AssetKind::Sol => {
+                let reward_rate = self.config.sol_reward_rate;
+                self.config.sol_accumulator.update(reward_rate, current)?;
+                self.config.sol_accumulator
+            }
+            AssetKind::Spl => {
+                let spl_pool = self.spl_pool.as_mut().ok_or(ErrorCode::MissingPool)?;
+                let reward_rate = spl_pool.reward_rate;
+                spl_pool.accumulator.update(reward_rate, current)?;
+                spl_pool.accumulator
+            }
+            AssetKind::Nft => {
+                let nft_pool = self.nft_pool.as_mut().ok_or(ErrorCode::MissingPool)?;
+                let reward_rate = nft_pool.reward_rate;
+                nft_pool.accumulator.update(reward_rate, current)?;
+                nft_pool.accumulator
+            }
+        };
+
+        let staked_amt = self.stake_account.staked_amt;
+        let reward_amount = accumulator.pending_reward(staked_amt, self.stake_account.reward_debt)?;
+
+        let claimed_from = self.stake_account.last_claimed_at;
+
+        self.stake_account.reward_debt = accumulator.reward_debt(staked_amt)?;
+        self.stake_account.last_claimed_at = current;
+
+        self.reward_user(reward_amount)?;
+
+        emit!(RewardsClaimed {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            amount: reward_amount,
+            claimed_from,
+            claimed_at: current,
+        });
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
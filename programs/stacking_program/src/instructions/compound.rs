@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{error::ErrorCode, events::Compounded, rewards::RewardPayout, AssetKind, SplPool, StakeAccount, StateConfig, UserAccount};
+
+#[derive(Accounts)]
+pub struct Compound <'info> {
+    // anyone can crank a position once its owner opted in
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+        mint::token_program = token_program,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = stake_account.auto_compound @ ErrorCode::CompoundingDisabled,
+        seeds = [b"stake", config.key().as_ref(), stake_account.owner.as_ref(), reward_mint.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", config.key().as_ref(), reward_mint.key().as_ref()],
+        bump = spl_pool.bump,
+    )]
+    pub spl_pool: Account<'info, SplPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = stake_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", stake_account.owner.as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl <'info> Compound <'info> {
+    // pays the position's pending reward straight into its vault and stakes it, the lock is left untouched
+    pub fn compound(&mut self) -> Result<()> {
+        require!(!self.config.is_paused(AssetKind::Spl), ErrorCode::StakingPaused);
+
+        let now = Clock::get()?.unix_timestamp;
+        let staked_amt = self.stake_account.staked_amt;
+
+        let reward_rate = self.spl_pool.reward_rate;
+        self.spl_pool.accumulator.update(reward_rate, now)?;
+        let reward_amount = self.spl_pool.accumulator.pending_reward(staked_amt, self.stake_account.reward_debt)?;
+
+        let vault_before = self.vault_ata.amount;
+
+        self.reward_user(reward_amount)?;
+
+        // a Token-2022 reward vault may withhold a transfer fee, only stake what arrived
+        self.vault_ata.reload()?;
+        let received = self.vault_ata.amount.checked_sub(vault_before).ok_or(ErrorCode::UnderFlow)?;
+
+        let new_staked_amt = staked_amt.checked_add(received).ok_or(ErrorCode::OverFlow)?;
+        self.spl_pool.accumulator.deposit(received)?;
+
+        self.stake_account.staked_amt = new_staked_amt;
+        self.stake_account.reward_debt = self.spl_pool.accumulator.reward_debt(new_staked_amt)?;
+        self.stake_account.last_claimed_at = now;
+
+        self.user_account.spl_staked_amount = self.user_account.spl_staked_amount.checked_add(received).ok_or(ErrorCode::OverFlow)?;
+
+        emit!(Compounded {
+            owner: self.stake_account.owner,
+            stake_account: self.stake_account.key(),
+            amount: received,
+            staked_amt: new_staked_amt,
+            compounded_at: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.stake_account.owner,
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.vault_ata, // restaked rewards never touch the owner's wallet
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{core_asset::MPL_CORE_ID, error::ErrorCode, events::NftPoolUpdated, NftCollectionPool, RewardAccumulator, StateConfig};
+
+#[derive(Accounts)]
+pub struct CreateCorePool <'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: a Metaplex Core collection, it has no mint so the pool is keyed by its address
+    #[account(owner = MPL_CORE_ID)]
+    pub collection: UncheckedAccount<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"nft_pool", config.key().as_ref(), collection.key().as_ref()],
+        bump,
+        space = 8 + NftCollectionPool::INIT_SPACE
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> CreateCorePool <'info> {
+    // Core pools are regular NFT pools, `update_nft_pool` manages them as well
+    pub fn create_core_pool(&mut self, reward_rate: u64, bumps: &CreateCorePoolBumps) -> Result<()> {
+        self.nft_pool.set_inner(NftCollectionPool {
+            collection_mint: self.collection.key(),
+            allowed: true,
+            reward_rate,
+            accumulator: RewardAccumulator::new(Clock::get()?.unix_timestamp),
+            bump: bumps.nft_pool,
+        });
+
+        emit!(NftPoolUpdated {
+            admin: self.admin.key(),
+            collection_mint: self.collection.key(),
+            reward_rate,
+            allowed: true,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::LockTiersUpdated, LockTier, LockTiers, StateConfig};
+
+#[derive(Accounts)]
+pub struct CreateLockTiers <'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump,
+        space = 8 + LockTiers::INIT_SPACE
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> CreateLockTiers <'info> {
+    pub fn create_lock_tiers(&mut self, tiers: Vec<LockTier>, bumps: &CreateLockTiersBumps) -> Result<()> {
+        LockTiers::validate(&tiers)?;
+
+        self.lock_tiers.set_inner(LockTiers {
+            tiers: tiers.clone(),
+            bump: bumps.lock_tiers,
+        });
+
+        emit!(LockTiersUpdated {
+            admin: self.admin.key(),
+            tiers,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{error::ErrorCode, events::NftPoolUpdated, NftCollectionPool, RewardAccumulator, StateConfig};
+
+#[derive(Accounts)]
+pub struct CreateNftPool <'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"nft_pool", config.key().as_ref(), collection_mint.key().as_ref()],
+        bump,
+        space = 8 + NftCollectionPool::INIT_SPACE
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> CreateNftPool <'info> {
+    pub fn create_nft_pool(&mut self, reward_rate: u64, bumps: &CreateNftPoolBumps) -> Result<()> {
+        self.nft_pool.set_inner(NftCollectionPool {
+            collection_mint: self.collection_mint.key(),
+            allowed: true,
+            reward_rate,
+            accumulator: RewardAccumulator::new(Clock::get()?.unix_timestamp),
+            bump: bumps.nft_pool,
+        });
+
+        emit!(NftPoolUpdated {
+            admin: self.admin.key(),
+            collection_mint: self.collection_mint.key(),
+            reward_rate,
+            allowed: true,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{error::ErrorCode, events::SplPoolUpdated, RewardAccumulator, SplPool, StateConfig};
+
+#[derive(Accounts)]
+pub struct CreateSplPool <'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"pool", config.key().as_ref(), mint.key().as_ref()],
+        bump,
+        space = 8 + SplPool::INIT_SPACE
+    )]
+    pub spl_pool: Account<'info, SplPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> CreateSplPool <'info> {
+    pub fn create_spl_pool(&mut self, reward_rate: u64, min_stake_amount: u64, max_stake_amount: u64, bumps: &CreateSplPoolBumps) -> Result<()> {
+        require!(min_stake_amount <= max_stake_amount, ErrorCode::InvalidStakeLimits);
+
+        self.spl_pool.set_inner(SplPool {
+            mint: self.mint.key(),
+            reward_rate,
+            min_stake_amount,
+            max_stake_amount,
+            accumulator: RewardAccumulator::new(Clock::get()?.unix_timestamp),
+            enabled: true,
+            bump: bumps.spl_pool,
+        });
+
+        emit!(SplPoolUpdated {
+            admin: self.admin.key(),
+            mint: self.mint.key(),
+            reward_rate,
+            min_stake_amount,
+            max_stake_amount,
+            enabled: true,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::StakeDeactivated, native_stake::deactivate_native_stake, StakeAccount, StateConfig};
+
+#[derive(Accounts)]
+pub struct DeactivateStakeSOl <'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        seeds = [b"vault", stake_account.key().as_ref()],
+        bump = stake_account.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: native stake account created by `delegate_stake_sol`
+    #[account(
+        mut,
+        seeds = [b"native_stake", stake_account.key().as_ref()],
+        bump = stake_account.native_stake_bump,
+    )]
+    pub native_stake: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK: native stake program
+    #[account(address = solana_sdk_ids::stake::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+impl <'info> DeactivateStakeSOl <'info> {
+    // the only way to start the cooldown, `withdraw_stake_sol` or `unstake_sol` can bring the principal back once the epoch has ended
+    pub fn deactivate_stake_sol(&mut self) -> Result<()> {
+        require!(self.stake_account.stake_activation_epoch.is_some(), ErrorCode::StakeNotDelegated);
+        require!(self.stake_account.stake_deactivation_epoch.is_none(), ErrorCode::StakeNotDelegated);
+
+        let stake_key = self.stake_account.key();
+
+        let vault_seeds = &[
+            b"vault",
+            stake_key.as_ref(),
+            &[self.stake_account.vault_bump],
+        ];
+
+        deactivate_native_stake(self.native_stake.to_account_info(), self.clock.to_account_info(), self.vault.to_account_info(), vault_seeds)?;
+
+        self.stake_account.stake_deactivation_epoch = Some(self.clock.epoch);
+
+        emit!(StakeDeactivated {
+            owner: self.user.key(),
+            stake_account: stake_key,
+            deactivation_epoch: self.clock.epoch,
+        });
+
+        Ok(())
+    }
+}
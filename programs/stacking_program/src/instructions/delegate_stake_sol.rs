@@ -0,0 +1,144 @@
+use anchor_lang::{prelude::*, solana_program::program::{invoke, invoke_signed}, system_program::{transfer, Transfer}};
+use solana_stake_interface::{instruction as stake_instruction, state::{Authorized, Lockup, StakeStateV2}};
+
+use crate::{error::ErrorCode, events::StakeDelegated, pda_account::create_pda_account, AssetKind, StakeAccount, StateConfig};
+
+#[derive(Accounts)]
+pub struct DelegateStakeSOl <'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    // staker and withdrawer authority of the native stake account
+    #[account(
+        mut,
+        seeds = [b"vault", stake_account.key().as_ref()],
+        bump = stake_account.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: created here as a native stake account owned by the stake program
+    #[account(
+        mut,
+        seeds = [b"native_stake", stake_account.key().as_ref()],
+        bump,
+    )]
+    pub native_stake: UncheckedAccount<'info>,
+
+    /// CHECK: must be the validator chosen by the admin, the stake program validates the vote state
+    #[account(
+        constraint = config.validator_vote == Some(vote_account.key()) @ ErrorCode::ValidatorNotSet,
+    )]
+    pub vote_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: stake history sysvar
+    #[account(address = solana_sdk_ids::sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: legacy stake config account, still expected by `DelegateStake`
+    #[account(address = solana_stake_interface::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    /// CHECK: native stake program
+    #[account(address = solana_sdk_ids::stake::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> DelegateStakeSOl <'info> {
+    // Moves the position's principal out of the vault into a native stake account delegated to the
+    // configured validator. Rewards keep accruing as before, the native staking yield is paid on withdrawal.
+    pub fn delegate_stake_sol(&mut self, bumps: &DelegateStakeSOlBumps) -> Result<()> {
+        require!(!self.config.is_paused(AssetKind::Sol), ErrorCode::StakingPaused);
+        require!(self.stake_account.asset_kind == AssetKind::Sol, ErrorCode::InvalidAssetKind);
+        require!(self.stake_account.stake_activation_epoch.is_none(), ErrorCode::StakeIsDelegated);
+
+        let stake_key = self.stake_account.key();
+        let amount = self.stake_account.staked_amt;
+
+        let vault_seeds = &[
+            b"vault",
+            stake_key.as_ref(),
+            &[self.stake_account.vault_bump],
+        ];
+
+        let signer_seeds = &[&vault_seeds[..]];
+
+        let native_stake_seeds = &[
+            b"native_stake",
+            stake_key.as_ref(),
+            &[bumps.native_stake],
+        ];
+
+        let native_stake_signer_seeds = &[&native_stake_seeds[..]];
+
+        // the user funds the stake account's rent, it is returned with the native yield on withdrawal
+        create_pda_account(
+            self.user.to_account_info(),
+            self.native_stake.to_account_info(),
+            self.system_program.to_account_info(),
+            StakeStateV2::size_of(),
+            &solana_sdk_ids::stake::ID,
+            native_stake_signer_seeds,
+        )?;
+
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.native_stake.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(self.system_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, amount)?;
+
+        invoke(
+            &stake_instruction::initialize(&self.native_stake.key(), &Authorized::auto(&self.vault.key()), &Lockup::default()),
+            &[
+                self.native_stake.to_account_info(),
+                self.rent.to_account_info(),
+            ],
+        )?;
+
+        invoke_signed(
+            &stake_instruction::delegate_stake(&self.native_stake.key(), &self.vault.key(), &self.vote_account.key()),
+            &[
+                self.native_stake.to_account_info(),
+                self.vote_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_config.to_account_info(),
+                self.vault.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        self.stake_account.native_stake_bump = bumps.native_stake;
+        self.stake_account.stake_activation_epoch = Some(self.clock.epoch);
+        self.stake_account.stake_deactivation_epoch = None;
+
+        emit!(StakeDelegated {
+            owner: self.user.key(),
+            stake_account: stake_key,
+            vote_account: self.vote_account.key(),
+            amount,
+            activation_epoch: self.clock.epoch,
+        });
+
+        Ok(())
+    }
+}
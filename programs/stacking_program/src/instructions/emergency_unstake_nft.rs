@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{metadata::{Metadata, MetadataAccount}, token::{Mint, Token, TokenAccount}};
+
+use crate::{error::ErrorCode, nft_lock::{is_programmable, NftLock}, events::EmergencyUnstaked, AssetKind, NftCollectionPool, StakeAccount, StateConfig, UserAccount};
+
+#[derive(Accounts)]
+pub struct EmergencyUnStakeNFT<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub mint_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = metadata_program.key(),
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    /// CHECK: master or print edition, may not exist, `can_freeze` decides how the NFT is held
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    // escrow for NFTs that can't be frozen, omitted otherwise
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = stake_account,
+    )]
+    pub vault_ata: Option<Account<'info, TokenAccount>>,
+
+    // programmable NFTs only, omitted for legacy NFTs
+    /// CHECK: token record of `mint_ata`, validated by the token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            mint_ata.key().as_ref()
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the rule set of a programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token auth rules program, validated by the token metadata program
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = mint,
+        constraint = stake_account.owner == user.key() @ ErrorCode::InvalidStakeOwner,
+        seeds = [b"stake", config.key().as_ref(), mint.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), stake_account.collection.as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> EmergencyUnStakeNFT<'info> {
+    // NFTs carry no principal penalty, an early exit only forfeits the accrued rewards
+    pub fn emergency_unstake_nft(&mut self) -> Result<()> {
+
+        let staked_at = self.stake_account.staked_at;
+        let current = Clock::get()?.unix_timestamp;
+
+        let seeds = &[
+            b"stake",
+            self.config.to_account_info().key.as_ref(),
+            self.mint.to_account_info().key.as_ref(),
+            &self.stake_account.seed.to_le_bytes()[..],
+            &[self.stake_account.bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        NftLock {
+            owner: self.user.to_account_info(),
+            delegate: self.stake_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_account: self.mint_ata.to_account_info(),
+            metadata: self.metadata.to_account_info(),
+            edition: self.master_edition.to_account_info(),
+            programmable: is_programmable(&self.metadata),
+            escrowed: self.stake_account.escrowed,
+            vault: self.vault_ata.as_ref().map(|account| account.to_account_info()),
+            token_record: self.token_record.as_ref().map(|account| account.to_account_info()),
+            authorization_rules: self.authorization_rules.as_ref().map(|account| account.to_account_info()),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(|account| account.to_account_info()),
+            sysvar_instructions: self.sysvar_instructions.as_ref().map(|account| account.to_account_info()),
+            token_program: self.token_program.to_account_info(),
+            metadata_program: self.metadata_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }.unlock(signer_seeds)?;
+
+        self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_sub(1).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.nft_pool.reward_rate;
+        self.nft_pool.accumulator.update(reward_rate, current)?;
+        self.nft_pool.accumulator.withdraw(self.stake_account.staked_amt)?;
+
+        emit!(EmergencyUnstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Nft,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            penalty: 0,
+            seed: self.stake_account.seed,
+            staked_at,
+            unstaked_at: current,
+        });
+
+        Ok(())
+    }
+}
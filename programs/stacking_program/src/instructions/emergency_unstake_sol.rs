@@ -0,0 +1,118 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+
+use crate::{error::ErrorCode, events::EmergencyUnstaked, reward_math::early_unstake_penalty, AssetKind, StakeAccount, StateConfig, Treasury, UserAccount};
+
+#[derive(Accounts)]
+pub struct EmergencyUnStakeSOl <'info> {
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", stake_account.key().as_ref()],
+        bump = stake_account.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> EmergencyUnStakeSOl <'info> {
+    // exits at any time, all accrued rewards are forfeited and an early exit pays the penalty
+    pub fn emergency_unstake_sol(&mut self) -> Result<()> {
+
+        // a delegated principal has to be deactivated and withdrawn back into the vault first
+        require!(self.stake_account.stake_activation_epoch.is_none(), ErrorCode::StakeIsDelegated);
+
+        let staked_at = self.stake_account.staked_at;
+        let current = Clock::get()?.unix_timestamp;
+        let time_passed = current.checked_sub(staked_at).ok_or(ErrorCode::UnderFlow)?;
+
+        // the lock is already waived while paused
+        let penalty = if self.config.is_paused(AssetKind::Sol) {
+            0
+        } else {
+            early_unstake_penalty(self.stake_account.staked_amt, self.config.early_unstake_penalty_bps, time_passed, self.stake_account.lock_period)?
+        };
+
+        let stake_key = self.stake_account.key();
+        let seeds = &[
+            b"vault",
+            stake_key.as_ref(),
+            &[self.stake_account.vault_bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let vault_balance = self.vault.lamports();
+
+        if penalty > 0 {
+            let cpi_accounts = Transfer {
+                from: self.vault.to_account_info(),
+                to: self.treasury.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(self.system_program.to_account_info(), cpi_accounts, signer_seeds);
+            transfer(cpi_ctx, penalty)?;
+        }
+
+        // the rest of the principal and the vault's rent reserve go back to the user
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.user.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(self.system_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, vault_balance.checked_sub(penalty).ok_or(ErrorCode::UnderFlow)?)?;
+
+        self.user_account.sol_staked_amount = self.user_account.sol_staked_amount.checked_sub(self.stake_account.staked_amt).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.config.sol_reward_rate;
+        self.config.sol_accumulator.update(reward_rate, current)?;
+        self.config.sol_accumulator.withdraw(self.stake_account.staked_amt)?;
+
+        emit!(EmergencyUnstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Sol,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            penalty,
+            seed: self.stake_account.seed,
+            staked_at,
+            unstaked_at: current,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{error::ErrorCode, events::EmergencyUnstaked, reward_math::early_unstake_penalty, token_vault::harvest_withheld_fees, AssetKind, SplPool, StakeAccount, StateConfig, Treasury, UserAccount};
+
+#[derive(Accounts)]
+pub struct EmergencyUnStakeSPL <'info> {
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut, // Token-2022 fees withheld in the vault are harvested to the mint before it is closed
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub mint_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), mint.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", config.key().as_ref(), mint.key().as_ref()],
+        bump = spl_pool.bump,
+    )]
+    pub spl_pool: Account<'info, SplPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = stake_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    // created off-chain, anyone can open the treasury's ATA for a new mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>, // classic SPL Token or Token-2022, for the staked mint
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> EmergencyUnStakeSPL <'info> {
+    // exits at any time, all accrued rewards are forfeited and an early exit pays the penalty
+    pub fn emergency_unstake_spl(&mut self) -> Result<()> {
+
+        let staked_at = self.stake_account.staked_at;
+        let current = Clock::get()?.unix_timestamp;
+        let time_passed = current.checked_sub(staked_at).ok_or(ErrorCode::UnderFlow)?;
+
+        // the lock is already waived while paused
+        let penalty = if self.config.is_paused(AssetKind::Spl) {
+            0
+        } else {
+            early_unstake_penalty(self.stake_account.staked_amt, self.config.early_unstake_penalty_bps, time_passed, self.stake_account.lock_period)?
+        };
+
+        let seeds = &[
+            b"stake",
+            self.config.to_account_info().key.as_ref(),
+            self.user.to_account_info().key.as_ref(),
+            self.mint.to_account_info().key.as_ref(),
+            &self.stake_account.seed.to_le_bytes(),
+            &[self.stake_account.bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        if penalty > 0 {
+            let cpi_accounts = TransferChecked {
+                from: self.vault_ata.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.treasury_ata.to_account_info(),
+                authority: self.stake_account.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+            transfer_checked(cpi_ctx, penalty, self.mint.decimals)?;
+        }
+
+        let remaining = self.vault_ata.amount.checked_sub(penalty).ok_or(ErrorCode::UnderFlow)?;
+
+        let cpi_accounts = TransferChecked {
+            from: self.vault_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.mint_ata.to_account_info(),
+            authority: self.stake_account.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        transfer_checked(cpi_ctx, remaining, self.mint.decimals)?;
+
+        harvest_withheld_fees(self.token_program.to_account_info(), self.mint.to_account_info(), self.vault_ata.to_account_info())?;
+
+        let close_accounts = CloseAccount {
+            account: self.vault_ata.to_account_info(),
+            destination: self.user.to_account_info(),
+            authority: self.stake_account.to_account_info()
+        };
+
+        let close_cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer_seeds);
+
+        close_account(close_cpi_ctx)?;
+
+        self.user_account.spl_staked_amount = self.user_account.spl_staked_amount.checked_sub(self.stake_account.staked_amt).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.spl_pool.reward_rate;
+        self.spl_pool.accumulator.update(reward_rate, current)?;
+        self.spl_pool.accumulator.withdraw(self.stake_account.staked_amt)?;
+
+        emit!(EmergencyUnstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Spl,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            penalty,
+            seed: self.stake_account.seed,
+            staked_at,
+            unstaked_at: current,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+
+use crate::{error::ErrorCode, events::RewardsFunded, RewardMode, StateConfig};
+
+#[derive(Accounts)]
+pub struct FundRewards <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        address = config.reward_mint,
+    )]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = admin,
+    )]
+    pub admin_reward_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl <'info> FundRewards <'info> {
+    pub fn fund_rewards(&mut self, amount: u64) -> Result<()> {
+        require!(self.config.reward_mode == RewardMode::Vault, ErrorCode::InvalidRewardMode);
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.admin_reward_ata.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.admin.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.reward_mint.decimals)?;
+
+        self.reward_vault.reload()?;
+
+        emit!(RewardsFunded {
+            admin: self.admin.key(),
+            amount,
+            vault_balance: self.reward_vault.amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+use anchor_spl::{token::Token, token_interface::{Mint, TokenAccount}};
+
+use crate::{error::ErrorCode, events::StakeIncreased, reward_math::{apply_multiplier, apr_yield}, rewards::RewardPayout, AssetKind, LockTiers, StakeAccount, StateConfig, UserAccount};
+
+#[derive(Accounts)]
+pub struct IncreaseStakeSOl <'info> {
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", stake_account.key().as_ref()],
+        bump = stake_account.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> IncreaseStakeSOl <'info> {
+    // Adds `amount` to an existing position. Rewards accrued so far are paid out first, then the position
+    // restarts at `staked_at = now` so the lock and APR run on the whole new balance from here on.
+    pub fn increase_stake_sol(&mut self, amount: u64, lock_period: Option<i64>) -> Result<()> {
+        require!(!self.config.is_paused(AssetKind::Sol), ErrorCode::StakingPaused);
+
+        // a delegated principal has to be deactivated and withdrawn back into the vault first
+        require!(self.stake_account.stake_activation_epoch.is_none(), ErrorCode::StakeIsDelegated);
+
+        require!(amount > 0, ErrorCode::StakeAmountTooLow);
+
+        let cpi_program = self.system_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.user.to_account_info(),
+            to: self.vault.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        transfer(cpi_ctx, amount)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let time_passed = now.checked_sub(self.stake_account.staked_at).ok_or(ErrorCode::UnderFlow)?;
+        let staked_amt = self.stake_account.staked_amt;
+
+        let reward_rate = self.config.sol_reward_rate;
+        self.config.sol_accumulator.update(reward_rate, now)?;
+        let mut reward_amount = self.config.sol_accumulator.pending_reward(staked_amt, self.stake_account.reward_debt)?;
+
+        if self.stake_account.locked_stackers {
+            let base_yield = apr_yield(staked_amt, self.config.annaul_percentage_rate, time_passed)?;
+            let yield_amt = apply_multiplier(base_yield, self.stake_account.lock_multiplier_bps)?;
+            reward_amount = reward_amount.checked_add(yield_amt).ok_or(ErrorCode::OverFlow)?;
+        }
+
+        if let Some(lock_period) = lock_period {
+            let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+            self.stake_account.extend_lock(tier)?;
+        }
+
+        let new_staked_amt = staked_amt.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+        self.config.sol_accumulator.deposit(amount)?;
+
+        self.stake_account.staked_amt = new_staked_amt;
+        self.stake_account.reward_debt = self.config.sol_accumulator.reward_debt(new_staked_amt)?;
+        self.stake_account.staked_at = now;
+        self.stake_account.last_claimed_at = now;
+
+        self.user_account.sol_staked_amount = self.user_account.sol_staked_amount.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+
+        emit!(StakeIncreased {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Sol,
+            mint: self.stake_account.mint,
+            amount,
+            staked_amt: new_staked_amt,
+            lock_period: self.stake_account.lock_period,
+            staked_at: now,
+        });
+
+        self.reward_user(reward_amount)?;
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::Token, token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked}};
+
+use crate::{error::ErrorCode, events::StakeIncreased, reward_math::{apply_multiplier, apr_yield}, rewards::RewardPayout, AssetKind, LockTiers, SplPool, StakeAccount, StateConfig, UserAccount};
+
+#[derive(Accounts)]
+pub struct IncreaseStakeSPL <'info> {
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub mint_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+        mint::token_program = reward_token_program,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), mint.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", config.key().as_ref(), mint.key().as_ref()],
+        bump = spl_pool.bump,
+    )]
+    pub spl_pool: Account<'info, SplPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = stake_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>, // classic SPL Token or Token-2022, for the staked mint
+    pub reward_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> IncreaseStakeSPL <'info> {
+    // Adds `amount` to an existing position. Rewards accrued so far are paid out first, then the position
+    // restarts at `staked_at = now` so the lock and APR run on the whole new balance from here on.
+    pub fn increase_stake_spl(&mut self, amount: u64, lock_period: Option<i64>) -> Result<()> {
+        require!(!self.config.is_paused(AssetKind::Spl), ErrorCode::StakingPaused);
+        require!(self.spl_pool.enabled, ErrorCode::PoolDisabled);
+
+        let vault_before = self.vault_ata.amount;
+
+        let cpi_accounts = TransferChecked {
+            from: self.mint_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault_ata.to_account_info(),
+            authority: self.user.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+        // Token-2022 transfer fees are withheld from the vault, only credit what it actually received
+        self.vault_ata.reload()?;
+        let received = self.vault_ata.amount.checked_sub(vault_before).ok_or(ErrorCode::UnderFlow)?;
+        require!(received > 0, ErrorCode::StakeAmountTooLow);
+
+        let staked_amt = self.stake_account.staked_amt;
+        let new_staked_amt = staked_amt.checked_add(received).ok_or(ErrorCode::OverFlow)?;
+        require!(new_staked_amt <= self.spl_pool.max_stake_amount, ErrorCode::StakeAmountTooHigh);
+
+        let now = Clock::get()?.unix_timestamp;
+        let time_passed = now.checked_sub(self.stake_account.staked_at).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.spl_pool.reward_rate;
+        self.spl_pool.accumulator.update(reward_rate, now)?;
+        let mut reward_amount = self.spl_pool.accumulator.pending_reward(staked_amt, self.stake_account.reward_debt)?;
+
+        if self.stake_account.locked_stackers {
+            let base_yield = apr_yield(staked_amt, self.config.annaul_percentage_rate, time_passed)?;
+            let yield_amt = apply_multiplier(base_yield, self.stake_account.lock_multiplier_bps)?;
+            reward_amount = reward_amount.checked_add(yield_amt).ok_or(ErrorCode::OverFlow)?;
+        }
+
+        if let Some(lock_period) = lock_period {
+            let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+            self.stake_account.extend_lock(tier)?;
+        }
+
+        self.spl_pool.accumulator.deposit(received)?;
+
+        self.stake_account.staked_amt = new_staked_amt;
+        self.stake_account.reward_debt = self.spl_pool.accumulator.reward_debt(new_staked_amt)?;
+        self.stake_account.staked_at = now;
+        self.stake_account.last_claimed_at = now;
+
+        self.user_account.spl_staked_amount = self.user_account.spl_staked_amount.checked_add(received).ok_or(ErrorCode::OverFlow)?;
+
+        emit!(StakeIncreased {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Spl,
+            mint: self.stake_account.mint,
+            amount: received,
+            staked_amt: new_staked_amt,
+            lock_period: self.stake_account.lock_period,
+            staked_at: now,
+        });
+
+        self.reward_user(reward_amount)?;
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.reward_token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
 
-use crate::{error::ErrorCode, StateConfig, ADMIN};
+use crate::{error::ErrorCode, RewardAccumulator, RewardMode, StateConfig, ADMIN};
 
 #[derive(Accounts)]
 pub struct InitializeConfig <'info> {
@@ -20,6 +20,7 @@ pub struct InitializeConfig <'info> {
     )]
     pub config: Account<'info, StateConfig>,
 
+    // RewardMode::Mint
     #[account(
         init,
         payer = admin,
@@ -28,32 +29,68 @@ pub struct InitializeConfig <'info> {
         mint::decimals = 6,
         mint::authority = config,
     )]
-    pub reward_mint: Account<'info, Mint>,
+    pub reward_mint: Option<Account<'info, Mint>>,
+
+    // RewardMode::Vault, an existing mint whose supply is managed outside the program
+    pub vault_reward_mint: Option<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = vault_reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<Account<'info, TokenAccount>>,
 
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>
 }
 
 impl <'info> InitializeConfig <'info> {
     pub fn initialize_config(
         &mut self,
-        points_per_nft_stake: u8,
-        points_per_sol_stake: u8,
-        points_per_spl_stake: u8,
+        sol_reward_rate: u64,
         min_freeze_period: i64,
         annaul_percentage_rate: u16,
+        reward_mode: RewardMode,
         bumps: &InitializeConfigBumps,
     ) -> Result<()> {
+        let (reward_mint, rewards_bump) = match reward_mode {
+            RewardMode::Mint => {
+                let reward_mint = self.reward_mint.as_ref().ok_or(ErrorCode::InvalidRewardAccounts)?;
+                (reward_mint.key(), bumps.reward_mint.ok_or(ErrorCode::InvalidRewardAccounts)?)
+            }
+            RewardMode::Vault => {
+                let reward_mint = self.vault_reward_mint.as_ref().ok_or(ErrorCode::InvalidRewardAccounts)?;
+                require!(self.reward_vault.is_some(), ErrorCode::InvalidRewardAccounts);
+                (reward_mint.key(), 0)
+            }
+        };
+
+        let clock = Clock::get()?;
+
         self.config.set_inner(StateConfig {
-            points_per_nft_stake,
-            points_per_sol_stake,
-            points_per_spl_stake,
+            admin: self.admin.key(),
+            pending_admin: None,
+            sol_reward_rate,
             min_freeze_period,
             annaul_percentage_rate,
-            rewards_bump: bumps.reward_mint, 
+            early_unstake_penalty_bps: 0,
+            sol_accumulator: RewardAccumulator::new(clock.unix_timestamp),
+            reward_mode,
+            reward_mint,
+            max_total_emission: u64::MAX, // uncapped until the admin sets limits through update_config
+            total_emitted: 0,
+            epoch_emission_budget: u64::MAX,
+            current_epoch: clock.epoch,
+            epoch_emitted: 0,
+            validator_vote: None,
+            paused: 0,
+            rewards_bump,
             bump: bumps.config
         });
 
-        Ok(())
+        self.config.validate()
     }
 }
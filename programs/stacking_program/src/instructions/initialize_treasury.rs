@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, StateConfig, Treasury};
+
+#[derive(Accounts)]
+pub struct InitializeTreasury <'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump,
+        space = 8 + Treasury::INIT_SPACE
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> InitializeTreasury <'info> {
+    pub fn initialize_treasury(&mut self, bumps: &InitializeTreasuryBumps) -> Result<()> {
+        self.treasury.set_inner(Treasury {
+            bump: bumps.treasury,
+        });
+
+        Ok(())
+    }
+}
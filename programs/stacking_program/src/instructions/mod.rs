@@ -1,8 +1,31 @@
 pub mod initialize_config;
+pub mod update_config;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod set_paused;
+pub mod create_spl_pool;
+pub mod update_spl_pool;
+pub mod create_nft_pool;
+pub mod update_nft_pool;
+pub mod create_core_pool;
+pub mod create_lock_tiers;
+pub mod update_lock_tiers;
+pub mod fund_rewards;
+pub mod initialize_treasury;
+pub mod withdraw_treasury_sol;
+pub mod withdraw_treasury_spl;
 pub mod initialize_user;
 
 pub mod stake_nft;
 pub mod unstake_nft;
+pub mod stake_nft_batch;
+pub mod unstake_nft_batch;
+
+pub mod stake_core_asset;
+pub mod unstake_core_asset;
+
+pub mod stake_cnft;
+pub mod unstake_cnft;
 
 pub mod  stake_sol;
 pub mod unstake_sol;
@@ -10,14 +33,69 @@ pub mod unstake_sol;
 pub mod stake_spl;
 pub mod unstake_spl;
 
+pub mod increase_stake_sol;
+pub mod increase_stake_spl;
+
+pub mod set_validator;
+pub mod delegate_stake_sol;
+pub mod deactivate_stake_sol;
+pub mod withdraw_stake_sol;
+
+pub mod claim_rewards;
+pub mod set_compounding;
+pub mod compound;
+
+pub mod emergency_unstake_nft;
+pub mod emergency_unstake_sol;
+pub mod emergency_unstake_spl;
+
 pub use initialize_config::*;
+pub use update_config::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use set_paused::*;
+pub use create_spl_pool::*;
+pub use update_spl_pool::*;
+pub use create_nft_pool::*;
+pub use update_nft_pool::*;
+pub use create_core_pool::*;
+pub use create_lock_tiers::*;
+pub use update_lock_tiers::*;
+pub use fund_rewards::*;
+pub use initialize_treasury::*;
+pub use withdraw_treasury_sol::*;
+pub use withdraw_treasury_spl::*;
 pub use initialize_user::*;
 
 pub use stake_nft::*;
 pub use unstake_nft::*;
+pub use stake_nft_batch::*;
+pub use unstake_nft_batch::*;
+
+pub use stake_core_asset::*;
+pub use unstake_core_asset::*;
+
+pub use stake_cnft::*;
+pub use unstake_cnft::*;
 
 pub use stake_sol::*;
 pub use unstake_sol::*;
 
 pub use stake_spl::*;
-pub use unstake_spl::*;
\ No newline at end of file
+pub use unstake_spl::*;
+
+pub use increase_stake_sol::*;
+pub use increase_stake_spl::*;
+
+pub use set_validator::*;
+pub use delegate_stake_sol::*;
+pub use deactivate_stake_sol::*;
+pub use withdraw_stake_sol::*;
+
+pub use claim_rewards::*;
+pub use set_compounding::*;
+pub use compound::*;
+
+pub use emergency_unstake_nft::*;
+pub use emergency_unstake_sol::*;
+pub use emergency_unstake_spl::*;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::AdminProposed, StateConfig};
+
+#[derive(Accounts)]
+pub struct ProposeAdmin <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+}
+
+impl <'info> ProposeAdmin <'info> {
+    pub fn propose_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        // the proposed key only takes over once it signs `accept_admin`
+        self.config.pending_admin = Some(new_admin);
+
+        emit!(AdminProposed {
+            admin: self.admin.key(),
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, AssetKind, StakeAccount, StateConfig};
+
+#[derive(Accounts)]
+pub struct SetCompounding <'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = stake_account.owner == user.key() @ ErrorCode::InvalidStakeOwner,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+}
+
+impl <'info> SetCompounding <'info> {
+    // Locked positions are excluded: their APR is paid on `staked_amt` for the whole time since `staked_at`,
+    // so restaked rewards would earn yield for time they were not staked.
+    pub fn set_compounding(&mut self, enabled: bool) -> Result<()> {
+        if enabled {
+            require!(self.stake_account.asset_kind == AssetKind::Spl, ErrorCode::CompoundingNotAllowed);
+            require!(self.stake_account.mint == self.config.reward_mint, ErrorCode::CompoundingNotAllowed);
+            require!(!self.stake_account.locked_stackers, ErrorCode::CompoundingNotAllowed);
+        }
+
+        self.stake_account.auto_compound = enabled;
+
+        Ok(())
+    }
+}
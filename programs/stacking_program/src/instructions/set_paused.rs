@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::PauseUpdated, StateConfig, PAUSE_ALL};
+
+#[derive(Accounts)]
+pub struct SetPaused <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+}
+
+impl <'info> SetPaused <'info> {
+    pub fn set_paused(&mut self, paused: u8) -> Result<()> {
+        require!(paused & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+
+        let old_paused = self.config.paused;
+        self.config.paused = paused;
+
+        emit!(PauseUpdated {
+            admin: self.admin.key(),
+            old_paused,
+            new_paused: paused,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::ValidatorUpdated, StateConfig};
+
+#[derive(Accounts)]
+pub struct SetValidator <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    /// CHECK: only its owner matters, omit it to disable delegation for new positions
+    #[account(owner = solana_sdk_ids::vote::ID)]
+    pub vote_account: Option<UncheckedAccount<'info>>,
+}
+
+impl <'info> SetValidator <'info> {
+    // positions already delegated stay with their validator until they are withdrawn
+    pub fn set_validator(&mut self) -> Result<()> {
+        self.config.validator_vote = self.vote_account.as_ref().map(|vote_account| vote_account.key());
+
+        emit!(ValidatorUpdated {
+            admin: self.admin.key(),
+            vote_account: self.config.validator_vote,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::{Mint, Token}, token_interface};
+
+use crate::{compressed_nft::{creator_hash, data_hash, get_asset_id, LeafTransfer, MetadataArgs, BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID}, error::ErrorCode, events::Staked, rewards::RewardPayout, AssetKind, LockTiers, NftCollectionPool, StakeAccount, StateConfig, UserAccount, NFT_STAKE_BONUS};
+
+#[derive(Accounts)]
+#[instruction(seed: u64, index: u32)]
+pub struct StakeCnft<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: the leaf's current delegate, the user when none was set
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: the compressed NFT, only its id is used
+    #[account(address = get_asset_id(&merkle_tree.key(), u64::from(index)) @ ErrorCode::InvalidCompressedNft)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: concurrent merkle tree holding the leaf, validated by the compression program
+    #[account(mut, owner = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_ID,
+    )]
+    pub tree_config: UncheckedAccount<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // becomes the owner of the leaf while it is staked
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"stake", config.key().as_ref(), asset.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + StakeAccount::INIT_SPACE
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), collection_mint.key().as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: SPL noop program
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL account compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(address = BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeCnft<'info> {
+    // Escrows the leaf with the stake account, the merkle proof is passed as `remaining_accounts`.
+    // `metadata` is hashed here so the collection check rests on the same data Bubblegum verifies.
+    #[allow(clippy::too_many_arguments)]
+    pub fn stake_cnft(&mut self, seed: u64, index: u32, locked_stakers: bool, lock_period: i64, root: [u8; 32], metadata: MetadataArgs, proof: &[AccountInfo<'info>], bumps: &StakeCnftBumps) -> Result<()> {
+
+        require!(!self.config.is_paused(AssetKind::Nft), ErrorCode::StakingPaused);
+
+        let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+        let lock_period = tier.duration;
+        require!(self.nft_pool.allowed, ErrorCode::CollectionNotAllowed);
+
+        let collection = metadata.collection.ok_or(ErrorCode::InvalidCompressedNft)?;
+        require!(collection.verified && collection.key == self.collection_mint.key(), ErrorCode::InvalidCompressedNft);
+
+        LeafTransfer {
+            tree_config: self.tree_config.to_account_info(),
+            leaf_owner: self.user.to_account_info(),
+            leaf_delegate: self.leaf_delegate.to_account_info(),
+            new_leaf_owner: self.stake_account.to_account_info(),
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+            proof,
+        }.transfer(root, data_hash(&metadata)?, creator_hash(&metadata.creators), index, &[])?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        let reward_rate = self.nft_pool.reward_rate;
+        let reward_debt = self.nft_pool.accumulator.join(reward_rate, 1, now)?;
+
+        self.stake_account.set_inner(StakeAccount {
+            collection: self.collection_mint.key(),
+            merkle_tree: self.merkle_tree.key(),
+            leaf_index: index,
+            ..StakeAccount::open(self.user.key(), AssetKind::Nft, self.asset.key(), 1, reward_debt, tier, locked_stakers, bumps.stake_account, seed, now)
+        });
+
+        emit!(Staked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Nft,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            seed,
+            lock_period,
+            locked_stackers: locked_stakers,
+            staked_at: now,
+        });
+
+        self.reward_user(NFT_STAKE_BONUS)?;
+
+        self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_add(1).ok_or(ErrorCode::OverFlow)?;
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::Token, token_interface::{Mint, TokenAccount}};
+
+use crate::{core_asset::{asset_owner_and_collection, CoreFreeze, MPL_CORE_ID}, error::ErrorCode, events::Staked, rewards::RewardPayout, AssetKind, LockTiers, NftCollectionPool, StakeAccount, StateConfig, UserAccount, NFT_STAKE_BONUS};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct StakeCoreAsset<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Metaplex Core asset, owner and collection are read from its data in the handler
+    #[account(mut, owner = MPL_CORE_ID)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: the asset's Core collection, must have an NFT pool
+    #[account(mut, owner = MPL_CORE_ID)]
+    pub collection: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"stake", config.key().as_ref(), asset.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + StakeAccount::INIT_SPACE
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), collection.key().as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = MPL_CORE_ID)]
+    pub core_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeCoreAsset<'info> {
+    // Core assets are accounted exactly like legacy NFTs, the asset address stands in for the mint
+    pub fn stake_core_asset(&mut self, seed: u64, locked_stakers: bool, lock_period: i64, bumps: &StakeCoreAssetBumps) -> Result<()> {
+
+        require!(!self.config.is_paused(AssetKind::Nft), ErrorCode::StakingPaused);
+
+        let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+        let lock_period = tier.duration;
+        require!(self.nft_pool.allowed, ErrorCode::CollectionNotAllowed);
+
+        let (owner, collection) = asset_owner_and_collection(&self.asset.try_borrow_data()?)?;
+        require_keys_eq!(owner, self.user.key(), ErrorCode::InvalidStakeOwner);
+        require!(collection == Some(self.collection.key()), ErrorCode::InvalidCoreAsset);
+
+        CoreFreeze {
+            owner: self.user.to_account_info(),
+            delegate: self.stake_account.to_account_info(),
+            asset: self.asset.to_account_info(),
+            collection: self.collection.to_account_info(),
+            core_program: self.core_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }.freeze()?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        let reward_rate = self.nft_pool.reward_rate;
+        let reward_debt = self.nft_pool.accumulator.join(reward_rate, 1, now)?;
+
+        self.stake_account.set_inner(StakeAccount {
+            collection: self.collection.key(),
+            ..StakeAccount::open(self.user.key(), AssetKind::Nft, self.asset.key(), 1, reward_debt, tier, locked_stakers, bumps.stake_account, seed, now)
+        });
+
+        emit!(Staked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Nft,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            seed,
+            lock_period,
+            locked_stackers: locked_stakers,
+            staked_at: now,
+        });
+
+        self.reward_user(NFT_STAKE_BONUS)?;
+
+        self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_add(1).ok_or(ErrorCode::OverFlow)?;
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
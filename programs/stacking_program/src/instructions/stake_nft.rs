@@ -1,15 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    metadata::{
-        mpl_token_metadata::instructions::{
-            FreezeDelegatedAccountCpi, FreezeDelegatedAccountCpiAccounts,
-        },
-        MasterEditionAccount, Metadata, MetadataAccount,
-    },
-    token::{approve, mint_to, Approve, Mint, MintTo, Token, TokenAccount},
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token::{Mint, Token, TokenAccount},
+    token_interface,
 };
 
-use crate::{error::ErrorCode, StakeAccount, StateConfig, UserAccount};
+use crate::{error::ErrorCode, events::Staked, nft_lock::{can_freeze, is_programmable, NftLock}, rewards::RewardPayout, AssetKind, LockTiers, NftCollectionPool, StakeAccount, StateConfig, UserAccount, NFT_STAKE_BONUS};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -30,18 +27,24 @@ pub struct StakeNFT<'info> {
 
     #[account(
         mut,
-        seeds = [b"rewards", config.key().as_ref()],
-        bump = config.rewards_bump,
-        mint::authority = config,
+        address = config.reward_mint,
     )]
-    pub reward_mint: Account<'info, Mint>,
+    pub reward_mint: InterfaceAccount<'info, token_interface::Mint>,
 
     #[account(
         mut,
         associated_token::mint = reward_mint,
         associated_token::authority = user,
     )]
-    pub user_reward_ata: Account<'info, TokenAccount>,
+    pub user_reward_ata: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(
         seeds = [
@@ -52,11 +55,12 @@ pub struct StakeNFT<'info> {
         bump,
         seeds::program = metadata_program.key(),
         constraint = metadata.collection.as_ref().unwrap().key.as_ref() == collection_mint.key().as_ref(),
-        constraint = metadata.collection.as_ref().unwrap().verified == true
+        constraint = metadata.collection.as_ref().unwrap().verified
 
     )]
     pub metadata: Account<'info, MetadataAccount>,
 
+    /// CHECK: master or print edition, may not exist, `can_freeze` decides how the NFT is held
     #[account(
         seeds = [
             b"metadata",
@@ -67,7 +71,42 @@ pub struct StakeNFT<'info> {
         bump,
         seeds::program = metadata_program.key()
     )]
-    pub master_edition: Account<'info, MasterEditionAccount>,
+    pub master_edition: UncheckedAccount<'info>,
+
+    // escrow for NFTs that can't be frozen, omitted otherwise
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = stake_account,
+    )]
+    pub vault_ata: Option<Account<'info, TokenAccount>>,
+
+    // programmable NFTs only, omitted for legacy NFTs
+    /// CHECK: token record of `mint_ata`, validated by the token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            mint_ata.key().as_ref()
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the rule set of a programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token auth rules program, validated by the token metadata program
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
 
     #[account(
         init, // if we stake -> unstake and then stake again this may fail
@@ -79,11 +118,25 @@ pub struct StakeNFT<'info> {
     pub stake_account: Account<'info, StakeAccount>,
 
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump,
     )]
     pub config: Account<'info, StateConfig>,
 
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), collection_mint.key().as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
     #[account(
         mut,
         seeds = [b"user", user.key().as_ref()],
@@ -92,6 +145,7 @@ pub struct StakeNFT<'info> {
     pub user_account: Account<'info, UserAccount>,
 
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
 }
@@ -99,25 +153,11 @@ pub struct StakeNFT<'info> {
 impl<'info> StakeNFT<'info> {
     pub fn stake_nft(&mut self, seed:u64, locked_stakers: bool, lock_period: i64, bumps: &StakeNFTBumps) -> Result<()> {
 
-        require!(lock_period >= self.config.min_freeze_period, ErrorCode::TooLessStakePeriod);
-
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_account = Approve {
-            to: self.mint_ata.to_account_info(),
-            delegate: self.stake_account.to_account_info(),
-            authority: self.user.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_account);
+        require!(!self.config.is_paused(AssetKind::Nft), ErrorCode::StakingPaused);
 
-        approve(cpi_ctx, 1)?;
-
-        let delegate = &self.stake_account.to_account_info();
-        let token_account = &self.mint_ata.to_account_info();
-        let edition = &self.master_edition.to_account_info();
-        let mint = &self.mint.to_account_info();
-        let token_program = &self.token_program.to_account_info();
-        let metadata_program = &self.metadata_program.to_account_info();
+        let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+        let lock_period = tier.duration;
+        require!(self.nft_pool.allowed, ErrorCode::CollectionNotAllowed);
 
         let seeds = &[
             b"stake",
@@ -128,36 +168,59 @@ impl<'info> StakeNFT<'info> {
         ];
 
         let signer_seeds = &[&seeds[..]];
-        FreezeDelegatedAccountCpi::new(
-            metadata_program,
-            FreezeDelegatedAccountCpiAccounts {
-                delegate,
-                token_account,
-                edition,
-                mint,
-                token_program,
-            },
-        )
-        .invoke_signed(signer_seeds)?;
-        
+
+        let escrowed = !can_freeze(&self.mint, &self.master_edition);
+        require!(!escrowed || (self.mint.supply == 1 && self.mint.decimals == 0), ErrorCode::NotAnNft);
+        // a frozen NFT never uses the escrow, creating it anyway would strand its rent
+        require!(escrowed || self.vault_ata.is_none(), ErrorCode::UnexpectedNftVault);
+
+        NftLock {
+            owner: self.user.to_account_info(),
+            delegate: self.stake_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_account: self.mint_ata.to_account_info(),
+            metadata: self.metadata.to_account_info(),
+            edition: self.master_edition.to_account_info(),
+            programmable: is_programmable(&self.metadata),
+            escrowed,
+            vault: self.vault_ata.as_ref().map(|account| account.to_account_info()),
+            token_record: self.token_record.as_ref().map(|account| account.to_account_info()),
+            authorization_rules: self.authorization_rules.as_ref().map(|account| account.to_account_info()),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(|account| account.to_account_info()),
+            sysvar_instructions: self.sysvar_instructions.as_ref().map(|account| account.to_account_info()),
+            token_program: self.token_program.to_account_info(),
+            metadata_program: self.metadata_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }.lock(signer_seeds)?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        let reward_rate = self.nft_pool.reward_rate;
+        let reward_debt = self.nft_pool.accumulator.join(reward_rate, 1, now)?;
 
         self.stake_account.set_inner(StakeAccount {
+            collection: self.collection_mint.key(),
+            escrowed,
+            ..StakeAccount::open(self.user.key(), AssetKind::Nft, self.mint.key(), 1, reward_debt, tier, locked_stakers, bumps.stake_account, seed, now)
+        });
+
+        emit!(Staked {
             owner: self.user.key(),
-            mint: self.mint.key(),
-            staked_amt: 1,
-            staked_at: Clock::get()?.unix_timestamp,
-            lock_period: lock_period,
-            locked_stackers: locked_stakers,
-            bump: bumps.stake_account,
-            // vault_bump: 0,
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Nft,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
             seed,
+            lock_period,
+            locked_stackers: locked_stakers,
+            staked_at: now,
         });
 
-        // let points_u64 = u64::try_from(self.config.points_per_nft_stake).or(Err(ErrorCode::OverFlow))?;
+        // let points_u64 = u64::from(self.config.points_per_nft_stake);
 
         // let reward_amount = points_u64.checked_mul(1_000_000u64).unwrap();
 
-        self.reward_user(100_000_000u64)?; // one time reward to stake NFT
+        self.reward_user(NFT_STAKE_BONUS)?;
 
         // self.user_account.points = self.user_account.points.checked_add(100_000_000u64).ok_or(ErrorCode::OverFlow)?;
         self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_add(1).ok_or(ErrorCode::OverFlow)?;
@@ -166,28 +229,16 @@ impl<'info> StakeNFT<'info> {
     }
 
     pub fn reward_user(&mut self, amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = MintTo {
-            mint: self.reward_mint.to_account_info(),
-            to: self.user_reward_ata.to_account_info(),
-            authority: self.config.to_account_info()
-        };
-
-        let seeds = &[
-            &b"config"[..],
-            &[self.config.bump]
-        ];
-
-        let signer_seeds = &[&seeds[..]];
-
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        mint_to(ctx, amount)?;
-
-        self.user_account.points = self.user_account.points.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
-
-        Ok(())
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
     }
 
 }
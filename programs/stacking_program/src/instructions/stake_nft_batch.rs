@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{metadata::Metadata, token::{Mint, Token}, token_interface};
+
+use crate::{error::ErrorCode, events::Staked, nft_lock::{BatchNft, BATCH_ACCOUNTS_PER_NFT}, pda_account::create_pda_account, rewards::RewardPayout, AssetKind, LockTiers, NftCollectionPool, StakeAccount, StateConfig, UserAccount, MAX_NFT_BATCH, NFT_STAKE_BONUS};
+
+#[derive(Accounts)]
+pub struct StakeNFTBatch<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), collection_mint.key().as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeNFTBatch<'info> {
+    // Stakes one NFT per entry of `seeds`, each with its `BatchNft` accounts in `remaining_accounts`.
+    // Every NFT gets its own stake account as with `stake_nft`, the stake rewards are paid in one transfer.
+    pub fn stake_nft_batch(&mut self, seeds: Vec<u64>, locked_stakers: bool, lock_period: i64, nfts: &'info [AccountInfo<'info>]) -> Result<()> {
+
+        require!(!self.config.is_paused(AssetKind::Nft), ErrorCode::StakingPaused);
+        require!(!seeds.is_empty() && seeds.len() <= MAX_NFT_BATCH, ErrorCode::InvalidNftBatch);
+        require!(nfts.len() == seeds.len() * BATCH_ACCOUNTS_PER_NFT, ErrorCode::InvalidNftBatch);
+
+        let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+        let lock_period = tier.duration;
+        require!(self.nft_pool.allowed, ErrorCode::CollectionNotAllowed);
+
+        let now = Clock::get()?.unix_timestamp;
+
+        let reward_rate = self.nft_pool.reward_rate;
+
+        let space = 8 + StakeAccount::INIT_SPACE;
+        let config_key = self.config.key();
+
+        for (seed, accounts) in seeds.iter().zip(nfts.chunks_exact(BATCH_ACCOUNTS_PER_NFT)) {
+            let nft = BatchNft::load(accounts, &self.user.key(), &self.collection_mint.key())?;
+            let mint_key = nft.mint.key();
+
+            let (stake_key, bump) = Pubkey::find_program_address(
+                &[b"stake", config_key.as_ref(), mint_key.as_ref(), &seed.to_le_bytes()[..]],
+                &crate::ID,
+            );
+            require_keys_eq!(nft.stake_account.key(), stake_key, ErrorCode::InvalidNftBatch);
+
+            let stake_seeds = &[
+                b"stake",
+                config_key.as_ref(),
+                mint_key.as_ref(),
+                &seed.to_le_bytes()[..],
+                &[bump],
+            ];
+
+            let signer_seeds = &[&stake_seeds[..]];
+
+            create_pda_account(
+                self.user.to_account_info(),
+                nft.stake_account.clone(),
+                self.system_program.to_account_info(),
+                space,
+                &crate::ID,
+                signer_seeds,
+            )?;
+
+            nft.nft_lock(
+                self.user.to_account_info(),
+                self.token_program.to_account_info(),
+                self.metadata_program.to_account_info(),
+                self.system_program.to_account_info(),
+            ).lock(signer_seeds)?;
+
+            let reward_debt = self.nft_pool.accumulator.join(reward_rate, 1, now)?;
+
+            let stake_account = StakeAccount {
+                collection: self.collection_mint.key(),
+                ..StakeAccount::open(self.user.key(), AssetKind::Nft, mint_key, 1, reward_debt, tier, locked_stakers, bump, *seed, now)
+            };
+
+            stake_account.try_serialize(&mut &mut nft.stake_account.try_borrow_mut_data()?[..])?;
+
+            emit!(Staked {
+                owner: self.user.key(),
+                stake_account: stake_key,
+                asset_kind: AssetKind::Nft,
+                mint: mint_key,
+                amount: 1,
+                seed: *seed,
+                lock_period,
+                locked_stackers: locked_stakers,
+                staked_at: now,
+            });
+        }
+
+        let count = seeds.len() as u64;
+        self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_add(count).ok_or(ErrorCode::OverFlow)?;
+
+        let reward_amount = NFT_STAKE_BONUS.checked_mul(count).ok_or(ErrorCode::OverFlow)?;
+        self.reward_user(reward_amount)?;
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: Pubkey::default(), // a batch payout covers several stake accounts
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
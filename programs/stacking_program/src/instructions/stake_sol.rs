@@ -1,7 +1,7 @@
 use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
-use anchor_spl::token::{mint_to, spl_token::native_mint, Mint, MintTo, Token, TokenAccount};
+use anchor_spl::{token::{spl_token::native_mint, Token}, token_interface::{Mint, TokenAccount}};
 
-use crate::{error::ErrorCode, StakeAccount, StateConfig, UserAccount};
+use crate::{error::ErrorCode, events::Staked, rewards::RewardPayout, AssetKind, LockTiers, StakeAccount, StateConfig, UserAccount, SOL_STAKE_BONUS};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -12,18 +12,24 @@ pub struct StakeSOl <'info> {
 
     #[account(
         mut,
-        seeds = [b"rewards", config.key().as_ref()],
-        bump = config.rewards_bump,
-        mint::authority = config,
+        address = config.reward_mint,
     )]
-    pub reward_mint: Account<'info, Mint>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = reward_mint,
         associated_token::authority = user,
     )]
-    pub user_reward_ata: Account<'info, TokenAccount>,
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
@@ -35,17 +41,24 @@ pub struct StakeSOl <'info> {
     pub stake_account: Account<'info, StakeAccount>,
     
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump,
     )]
     pub config: Account<'info, StateConfig>,
 
-    // #[account(
-    //     mut,
-    //     seeds = [b"vault", stake_account.key().as_ref()],
-    //     bump,
-    // )]
-    // pub vault: SystemAccount<'info>,
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", stake_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
 
     #[account(
         mut,
@@ -60,36 +73,52 @@ pub struct StakeSOl <'info> {
 
 impl <'info> StakeSOl <'info> {
     pub fn stake_sol(&mut self, seed: u64, amount: u64, locked_stakers: bool, lock_period: i64, bumps: &StakeSOlBumps) -> Result<()> {
-        require!(lock_period >= self.config.min_freeze_period, ErrorCode::TooLessStakePeriod);
+        require!(!self.config.is_paused(AssetKind::Sol), ErrorCode::StakingPaused);
+
+        let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+        let lock_period = tier.duration;
+
+        // the vault also holds a rent exempt reserve so withdrawing principal can never leave it below the minimum
+        let reserve = Rent::get()?.minimum_balance(0);
 
         let cpi_program = self.system_program.to_account_info();
         let cpi_accounts = Transfer {
             from: self.user.to_account_info(),
-            to: self.stake_account.to_account_info(),
+            to: self.vault.to_account_info(),
         };
 
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        transfer(cpi_ctx, amount)?;
+        transfer(cpi_ctx, amount.checked_add(reserve).ok_or(ErrorCode::OverFlow)?)?;
 
-        // let points_u64 = u64::try_from(self.config.points_per_sol_stake).or(Err(ErrorCode::OverFlow))?;
+        // let points_u64 = u64::from(self.config.points_per_sol_stake);
 
         // let reward_amount = points_u64.checked_mul(amount).unwrap(); // amount is already in lamports
 
         // self.user_account.points = self.user_account.points.checked_add(100_000_000u64).ok_or(ErrorCode::OverFlow)?;
         self.user_account.sol_staked_amount = self.user_account.sol_staked_amount.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
 
-        self.reward_user(100_000_000u64)?;
+        self.reward_user(SOL_STAKE_BONUS)?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        let reward_rate = self.config.sol_reward_rate;
+        let reward_debt = self.config.sol_accumulator.join(reward_rate, amount, now)?;
 
         self.stake_account.set_inner(StakeAccount {
+            vault_bump: bumps.vault,
+            ..StakeAccount::open(self.user.key(), AssetKind::Sol, native_mint::id(), amount, reward_debt, tier, locked_stakers, bumps.stake_account, seed, now)
+        });
+
+        emit!(Staked {
             owner: self.user.key(),
-            mint: native_mint::id(),
-            staked_amt: amount,
-            staked_at: Clock::get()?.unix_timestamp,
-            lock_period: lock_period,
-            locked_stackers: locked_stakers,
-            bump: bumps.stake_account,
-            // vault_bump: 0,
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Sol,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
             seed,
+            lock_period,
+            locked_stackers: locked_stakers,
+            staked_at: now,
         });
 
         Ok(())
@@ -97,28 +126,15 @@ impl <'info> StakeSOl <'info> {
     }
 
     pub fn reward_user(&mut self, amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = MintTo {
-            mint: self.reward_mint.to_account_info(),
-            to: self.user_reward_ata.to_account_info(),
-            authority: self.config.to_account_info()
-        };
-
-        let seeds = &[
-            &b"config"[..],
-            &[self.config.bump]
-        ];
-
-        let signer_seeds = &[&seeds[..]];
-
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        mint_to(ctx, amount)?;
-
-        self.user_account.points = self.user_account.points.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
-
-        Ok(())
-
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
     }
 }
\ No newline at end of file
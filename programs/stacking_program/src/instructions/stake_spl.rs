@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{mint_to, transfer_checked, Mint, MintTo, Token, TokenAccount, TransferChecked}};
-use crate::{error::ErrorCode, StakeAccount, StateConfig, UserAccount};
+use anchor_spl::{associated_token::AssociatedToken, token::Token, token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked}};
+use crate::{error::ErrorCode, events::Staked, rewards::RewardPayout, AssetKind, LockTiers, SplPool, StakeAccount, StateConfig, UserAccount, SPL_STAKE_BONUS};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -9,29 +9,42 @@ pub struct StakeSPL <'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub mint_ata: Account<'info, TokenAccount>,
+    pub mint_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [b"rewards", config.key().as_ref()],
-        bump = config.rewards_bump,
-        mint::authority = config,
+        address = config.reward_mint,
+        mint::token_program = reward_token_program,
     )]
-    pub reward_mint: Account<'info, Mint>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = reward_mint,
         associated_token::authority = user,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+        associated_token::token_program = reward_token_program,
     )]
-    pub user_reward_ata: Account<'info, TokenAccount>,
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
@@ -43,18 +56,33 @@ pub struct StakeSPL <'info> {
     pub stake_account: Account<'info, StakeAccount>,
     
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump,
     )]
     pub config: Account<'info, StateConfig>,
 
+    #[account(
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", config.key().as_ref(), mint.key().as_ref()],
+        bump = spl_pool.bump,
+    )]
+    pub spl_pool: Account<'info, SplPool>,
+
     #[account(
         init,
         payer = user,
         associated_token::mint = mint,
         associated_token::authority = stake_account,
+        associated_token::token_program = token_program,
     )]
-    pub vault_ata: Account<'info, TokenAccount>,
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
 
 
     #[account(
@@ -64,7 +92,8 @@ pub struct StakeSPL <'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>, // classic SPL Token or Token-2022, for the staked mint
+    pub reward_token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 } 
@@ -72,7 +101,11 @@ pub struct StakeSPL <'info> {
 impl <'info> StakeSPL <'info> {
 
     pub fn stake_spl(&mut self, seed:u64, amount: u64, locked_stakers: bool, lock_period: i64, bumps: &StakeSPLBumps) -> Result<()> {
-        require!(lock_period >= self.config.min_freeze_period, ErrorCode::TooLessStakePeriod);
+        require!(!self.config.is_paused(AssetKind::Spl), ErrorCode::StakingPaused);
+
+        let tier = self.lock_tiers.lock_tier(lock_period, self.config.min_freeze_period)?;
+        let lock_period = tier.duration;
+        require!(self.spl_pool.enabled, ErrorCode::PoolDisabled);
 
         let cpi_program = self.token_program.to_account_info();
         
@@ -87,47 +120,49 @@ impl <'info> StakeSPL <'info> {
 
         transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
 
-        let points_u64 = u64::try_from(self.config.points_per_spl_stake).or(Err(ErrorCode::OverFlow))?;
+        // Token-2022 transfer fees are withheld from the vault, only credit what it actually received
+        self.vault_ata.reload()?;
+        let received = self.vault_ata.amount;
+
+        require!(received >= self.spl_pool.min_stake_amount, ErrorCode::StakeAmountTooLow);
+        require!(received <= self.spl_pool.max_stake_amount, ErrorCode::StakeAmountTooHigh);
+
+        self.user_account.spl_staked_amount = self.user_account.spl_staked_amount.checked_add(received).ok_or(ErrorCode::OverFlow)?;
 
-        let reward_amount = points_u64.checked_mul(amount).unwrap(); // amount is already in lamports
+        self.reward_user(SPL_STAKE_BONUS)?;
 
-        self.user_account.points = self.user_account.points.checked_add(reward_amount).ok_or(ErrorCode::OverFlow)?;
-        self.user_account.spl_staked_amount = self.user_account.spl_staked_amount.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+        let now = Clock::get()?.unix_timestamp;
 
-        self.reward_user(reward_amount)?;
+        let reward_rate = self.spl_pool.reward_rate;
+        let reward_debt = self.spl_pool.accumulator.join(reward_rate, received, now)?;
 
-        self.stake_account.set_inner(StakeAccount {
+        self.stake_account.set_inner(StakeAccount::open(self.user.key(), AssetKind::Spl, self.mint.key(), received, reward_debt, tier, locked_stakers, bumps.stake_account, seed, now));
+
+        emit!(Staked {
             owner: self.user.key(),
-            mint: self.mint.key(),
-            staked_amt: amount,
-            staked_at: Clock::get()?.unix_timestamp,
-            lock_period: lock_period,
-            locked_stackers: locked_stakers,
-            bump: bumps.stake_account,
-            // vault_bump: 0,
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Spl,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
             seed,
+            lock_period,
+            locked_stackers: locked_stakers,
+            staked_at: now,
         });
 
         Ok(())
     }
-    pub fn reward_user(&mut self,amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = MintTo {
-            mint: self.reward_mint.to_account_info(),
-            to: self.user_reward_ata.to_account_info(),
-            authority: self.config.to_account_info()
-        };
-
-        let seeds = &[
-            &b"config"[..],
-            &[self.config.bump]
-        ];
 
-        let signer_seeds = &[&seeds[..]];
-
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        mint_to(ctx, amount)
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.reward_token_program.to_account_info(),
+        }.pay(amount)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::Token, token_interface::{Mint, TokenAccount}};
+
+use crate::{compressed_nft::{LeafTransfer, BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID}, error::ErrorCode, events::Unstaked, rewards::{settle_unstake, RewardPayout}, AssetKind, NftCollectionPool, StakeAccount, StateConfig, UserAccount};
+
+#[derive(Accounts)]
+pub struct UnStakeCnft<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: the staked compressed NFT, recorded as the stake account's mint
+    #[account(address = stake_account.mint)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: the tree recorded at stake time
+    #[account(mut, address = stake_account.merkle_tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_ID,
+    )]
+    pub tree_config: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = user,
+        constraint = stake_account.owner == user.key() @ ErrorCode::InvalidStakeOwner,
+        seeds = [b"stake", config.key().as_ref(), asset.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), stake_account.collection.as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: SPL noop program
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL account compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(address = BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnStakeCnft<'info> {
+    // transfers the leaf back to the user, the hashes and proof describe its current state in the tree
+    pub fn unstake_cnft(&mut self, root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32], proof: &[AccountInfo<'info>]) -> Result<()> {
+
+        let staked_at = self.stake_account.staked_at;
+        let current = Clock::get()?.unix_timestamp;
+
+        let time_passed = current.checked_sub(staked_at).unwrap();
+
+        let paused = self.config.is_paused(AssetKind::Nft);
+
+        // while paused the lock is waived so users can always recover their principal
+        require!(paused || time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
+
+        let seeds = &[
+            b"stake",
+            self.config.to_account_info().key.as_ref(),
+            self.asset.to_account_info().key.as_ref(),
+            &self.stake_account.seed.to_le_bytes()[..],
+            &[self.stake_account.bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        // Bubblegum resets the delegate to the new owner on transfer, so the stake account is both
+        LeafTransfer {
+            tree_config: self.tree_config.to_account_info(),
+            leaf_owner: self.stake_account.to_account_info(),
+            leaf_delegate: self.stake_account.to_account_info(),
+            new_leaf_owner: self.user.to_account_info(),
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+            proof,
+        }.transfer(root, data_hash, creator_hash, self.stake_account.leaf_index, signer_seeds)?;
+
+        self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_sub(1).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.nft_pool.reward_rate;
+        let (reward_amount, _) = settle_unstake(&mut self.nft_pool.accumulator, reward_rate, &self.stake_account, self.stake_account.staked_amt, self.config.annaul_percentage_rate, paused, current)?;
+
+        emit!(Unstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Nft,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            seed: self.stake_account.seed,
+            lock_period: self.stake_account.lock_period,
+            staked_at,
+            unstaked_at: current,
+            remaining: 0,
+            rewards_forfeited: paused,
+        });
+
+        self.reward_user(reward_amount)?;
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
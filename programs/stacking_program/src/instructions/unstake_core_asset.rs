@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::Token, token_interface::{Mint, TokenAccount}};
+
+use crate::{core_asset::{CoreFreeze, MPL_CORE_ID}, error::ErrorCode, events::Unstaked, rewards::{settle_unstake, RewardPayout}, AssetKind, NftCollectionPool, StakeAccount, StateConfig, UserAccount};
+
+#[derive(Accounts)]
+pub struct UnStakeCoreAsset<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: the staked Core asset, recorded as the stake account's mint
+    #[account(mut, address = stake_account.mint)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: the asset's Core collection recorded at stake time
+    #[account(mut, address = stake_account.collection)]
+    pub collection: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = user,
+        constraint = stake_account.owner == user.key() @ ErrorCode::InvalidStakeOwner,
+        seeds = [b"stake", config.key().as_ref(), asset.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), collection.key().as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = MPL_CORE_ID)]
+    pub core_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnStakeCoreAsset<'info> {
+    pub fn unstake_core_asset(&mut self) -> Result<()> {
+
+        let staked_at = self.stake_account.staked_at;
+        let current = Clock::get()?.unix_timestamp;
+
+        let time_passed = current.checked_sub(staked_at).unwrap();
+
+        let paused = self.config.is_paused(AssetKind::Nft);
+
+        // while paused the lock is waived so users can always recover their principal
+        require!(paused || time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
+
+        let seeds = &[
+            b"stake",
+            self.config.to_account_info().key.as_ref(),
+            self.asset.to_account_info().key.as_ref(),
+            &self.stake_account.seed.to_le_bytes()[..],
+            &[self.stake_account.bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        CoreFreeze {
+            owner: self.user.to_account_info(),
+            delegate: self.stake_account.to_account_info(),
+            asset: self.asset.to_account_info(),
+            collection: self.collection.to_account_info(),
+            core_program: self.core_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }.thaw(signer_seeds)?;
+
+        self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_sub(1).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.nft_pool.reward_rate;
+        let (reward_amount, _) = settle_unstake(&mut self.nft_pool.accumulator, reward_rate, &self.stake_account, self.stake_account.staked_amt, self.config.annaul_percentage_rate, paused, current)?;
+
+        emit!(Unstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Nft,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            seed: self.stake_account.seed,
+            lock_period: self.stake_account.lock_period,
+            staked_at,
+            unstaked_at: current,
+            remaining: 0,
+            rewards_forfeited: paused,
+        });
+
+        self.reward_user(reward_amount)?;
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
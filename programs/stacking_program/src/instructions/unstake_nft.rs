@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{metadata::{mpl_token_metadata::instructions::{ ThawDelegatedAccountCpi, ThawDelegatedAccountCpiAccounts}, MasterEditionAccount, Metadata, MetadataAccount}, token::{ mint_to, revoke, Mint, MintTo, Revoke, Token, TokenAccount}};
+use anchor_spl::{metadata::{Metadata, MetadataAccount}, token::{Mint, Token, TokenAccount}, token_interface};
 
-use crate::{error::ErrorCode, StakeAccount, StateConfig, UserAccount};
+use crate::{error::ErrorCode, nft_lock::{is_programmable, NftLock}, events::Unstaked, rewards::{settle_unstake, RewardPayout}, AssetKind, NftCollectionPool, StakeAccount, StateConfig, UserAccount};
 
 #[derive(Accounts)]
 pub struct UnStakeNFT<'info> {
@@ -21,18 +21,24 @@ pub struct UnStakeNFT<'info> {
 
     #[account(
         mut,
-        seeds = [b"rewards", config.key().as_ref()],
-        bump = config.rewards_bump,
-        mint::authority = config,
+        address = config.reward_mint,
     )]
-    pub reward_mint: Account<'info, Mint>,
+    pub reward_mint: InterfaceAccount<'info, token_interface::Mint>,
 
     #[account(
         mut,
         associated_token::mint = reward_mint,
         associated_token::authority = user,
     )]
-    pub user_reward_ata: Account<'info, TokenAccount>,
+    pub user_reward_ata: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(
         seeds = [
@@ -43,11 +49,12 @@ pub struct UnStakeNFT<'info> {
         bump,
         seeds::program = metadata_program.key(),
         constraint = metadata.collection.as_ref().unwrap().key.as_ref() == collection_mint.key().as_ref(),
-        constraint = metadata.collection.as_ref().unwrap().verified == true
+        constraint = metadata.collection.as_ref().unwrap().verified
 
     )]
     pub metadata: Account<'info, MetadataAccount>,
 
+    /// CHECK: master or print edition, may not exist, `can_freeze` decides how the NFT is held
     #[account(
         seeds = [
             b"metadata",
@@ -58,24 +65,66 @@ pub struct UnStakeNFT<'info> {
         bump,
         seeds::program = metadata_program.key()
     )]
-    pub master_edition: Account<'info, MasterEditionAccount>,
+    pub master_edition: UncheckedAccount<'info>,
+
+    // escrow for NFTs that can't be frozen, omitted otherwise
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = stake_account,
+    )]
+    pub vault_ata: Option<Account<'info, TokenAccount>>,
+
+    // programmable NFTs only, omitted for legacy NFTs
+    /// CHECK: token record of `mint_ata`, validated by the token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            mint_ata.key().as_ref()
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the rule set of a programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token auth rules program, validated by the token metadata program
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
 
     #[account(
         mut,
         close = user,
         has_one = mint,
+        constraint = stake_account.owner == user.key() @ ErrorCode::InvalidStakeOwner,
         seeds = [b"stake", config.key().as_ref(), mint.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
         bump = stake_account.bump,
-
     )]
     pub stake_account: Account<'info, StakeAccount>,
 
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump,
     )]
     pub config: Account<'info, StateConfig>,
 
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), collection_mint.key().as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
     #[account(
         mut,
         seeds = [b"user", user.key().as_ref()],
@@ -96,7 +145,10 @@ impl<'info> UnStakeNFT<'info> {
 
         let time_passed = current.checked_sub(staked_at).unwrap();
 
-        require!( time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
+        let paused = self.config.is_paused(AssetKind::Nft);
+
+        // while paused the lock is waived so users can always recover their principal
+        require!(paused || time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
 
         let seeds = &[
             b"stake",
@@ -108,79 +160,60 @@ impl<'info> UnStakeNFT<'info> {
 
         let signer_seeds = &[&seeds[..]];
 
-        let delegate = &self.stake_account.to_account_info();
-        let token_account = &self.mint_ata.to_account_info();
-        let edition = &self.master_edition.to_account_info();
-        let mint = &self.mint.to_account_info();
-        let token_program = &self.token_program.to_account_info();
-        let metadata_program = &self.metadata_program.to_account_info();
-
-        ThawDelegatedAccountCpi::new(
-            metadata_program,
-            ThawDelegatedAccountCpiAccounts {
-                delegate,
-                token_account,
-                edition,
-                mint,
-                token_program,
-            },
-        )
-        .invoke_signed(signer_seeds)?;
-
-       let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = Revoke{
-            source: self.mint_ata.to_account_info(),
-            authority: self.user.to_account_info()
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-
-        revoke(cpi_ctx)?;
-
-        let points_u64 = u64::try_from(self.config.points_per_nft_stake).or(Err(ErrorCode::OverFlow))?;
-        let time_passed_u64 = u64::try_from(time_passed).or(Err(ErrorCode::OverFlow))?;
-
-        let mut reward_amount: u64 = points_u64.checked_mul(time_passed_u64).ok_or(ErrorCode::OverFlow)?;
-
-        if self.stake_account.locked_stackers {
-            let annual_percentage_rate_u64 = u64::try_from(self.config.annaul_percentage_rate).or(Err(ErrorCode::OverFlow))?;
-            let yield_time_u64 = u64::try_from(self.stake_account.lock_period).or(Err(ErrorCode::OverFlow))?;
-            let yield_reward = yield_time_u64.checked_mul(points_u64).ok_or(ErrorCode::OverFlow)?;
-            let product: u64 = yield_reward.checked_mul(annual_percentage_rate_u64).ok_or(ErrorCode::OverFlow)?;
-            let yield_amt: u64 = product.checked_div(10_000u64).ok_or(ErrorCode::OverFlow)?;
-            reward_amount = reward_amount.checked_add(yield_amt).ok_or(ErrorCode::OverFlow)?;
-        }
+        NftLock {
+            owner: self.user.to_account_info(),
+            delegate: self.stake_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_account: self.mint_ata.to_account_info(),
+            metadata: self.metadata.to_account_info(),
+            edition: self.master_edition.to_account_info(),
+            programmable: is_programmable(&self.metadata),
+            escrowed: self.stake_account.escrowed,
+            vault: self.vault_ata.as_ref().map(|account| account.to_account_info()),
+            token_record: self.token_record.as_ref().map(|account| account.to_account_info()),
+            authorization_rules: self.authorization_rules.as_ref().map(|account| account.to_account_info()),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(|account| account.to_account_info()),
+            sysvar_instructions: self.sysvar_instructions.as_ref().map(|account| account.to_account_info()),
+            token_program: self.token_program.to_account_info(),
+            metadata_program: self.metadata_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }.unlock(signer_seeds)?;
 
         self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_sub(1).ok_or(ErrorCode::OverFlow)?;
+
+        let reward_rate = self.nft_pool.reward_rate;
+        let (reward_amount, _) = settle_unstake(&mut self.nft_pool.accumulator, reward_rate, &self.stake_account, self.stake_account.staked_amt, self.config.annaul_percentage_rate, paused, current)?;
+
+        emit!(Unstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Nft,
+            mint: self.stake_account.mint,
+            amount: self.stake_account.staked_amt,
+            seed: self.stake_account.seed,
+            lock_period: self.stake_account.lock_period,
+            staked_at,
+            unstaked_at: current,
+            remaining: 0,
+            rewards_forfeited: paused,
+        });
+
         self.reward_user(reward_amount)?;
-        self.user_account.points = self.user_account.points.checked_add(reward_amount).ok_or(ErrorCode::OverFlow)?;
         Ok(())
         
     }
 
     pub fn reward_user(&mut self, amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = MintTo {
-            mint: self.reward_mint.to_account_info(),
-            to: self.user_reward_ata.to_account_info(),
-            authority: self.config.to_account_info()
-        };
-
-        let seeds = &[
-            &b"config"[..],
-            &[self.config.bump]
-        ];
-
-        let signer_seeds = &[&seeds[..]];
-
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        mint_to(ctx, amount)?;
-
-
-        Ok(())
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
     }
 
 }
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{metadata::Metadata, token::Token, token_interface::{Mint, TokenAccount}};
+
+use crate::{error::ErrorCode, events::Unstaked, nft_lock::{BatchNft, BATCH_ACCOUNTS_PER_NFT}, rewards::{settle_unstake, RewardPayout}, AssetKind, NftCollectionPool, StakeAccount, StateConfig, UserAccount, MAX_NFT_BATCH};
+
+#[derive(Accounts)]
+pub struct UnStakeNFTBatch<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        address = config.reward_mint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), nft_pool.collection_mint.as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnStakeNFTBatch<'info> {
+    // Unstakes every NFT whose `BatchNft` accounts are in `remaining_accounts`, all from `nft_pool`.
+    // Each position is settled like `unstake_nft` and the rewards of the whole batch are paid in one transfer.
+    pub fn unstake_nft_batch(&mut self, nfts: &'info [AccountInfo<'info>]) -> Result<()> {
+
+        let count = nfts.len() / BATCH_ACCOUNTS_PER_NFT;
+        require!(count > 0 && count <= MAX_NFT_BATCH, ErrorCode::InvalidNftBatch);
+        require!(nfts.len() == count * BATCH_ACCOUNTS_PER_NFT, ErrorCode::InvalidNftBatch);
+
+        let current = Clock::get()?.unix_timestamp;
+        let paused = self.config.is_paused(AssetKind::Nft);
+
+        let reward_rate = self.nft_pool.reward_rate;
+        let annual_percentage_rate = self.config.annaul_percentage_rate;
+
+        let config_key = self.config.key();
+        let collection_mint = self.nft_pool.collection_mint;
+        let mut reward_amount: u64 = 0;
+
+        for accounts in nfts.chunks_exact(BATCH_ACCOUNTS_PER_NFT) {
+            let nft = BatchNft::load(accounts, &self.user.key(), &collection_mint)?;
+            let mint_key = nft.mint.key();
+
+            let stake_account = Account::<StakeAccount>::try_from(nft.stake_account)?;
+            require_keys_eq!(stake_account.owner, self.user.key(), ErrorCode::InvalidStakeOwner);
+            require!(stake_account.asset_kind == AssetKind::Nft && stake_account.mint == mint_key, ErrorCode::InvalidNftBatch);
+            require!(stake_account.collection == collection_mint && !stake_account.escrowed, ErrorCode::InvalidNftBatch);
+
+            let time_passed = current.checked_sub(stake_account.staked_at).unwrap();
+
+            // while paused the lock is waived so users can always recover their principal
+            require!(paused || time_passed >= stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
+
+            let seeds = &[
+                b"stake",
+                config_key.as_ref(),
+                mint_key.as_ref(),
+                &stake_account.seed.to_le_bytes()[..],
+                &[stake_account.bump],
+            ];
+
+            // the owner check above only trusts stake accounts at their own PDA
+            let stake_key = Pubkey::create_program_address(seeds, &crate::ID).or(Err(ErrorCode::InvalidNftBatch))?;
+            require_keys_eq!(stake_account.key(), stake_key, ErrorCode::InvalidNftBatch);
+
+            let signer_seeds = &[&seeds[..]];
+
+            nft.nft_lock(
+                self.user.to_account_info(),
+                self.token_program.to_account_info(),
+                self.metadata_program.to_account_info(),
+                self.system_program.to_account_info(),
+            ).unlock(signer_seeds)?;
+
+            let (reward, _) = settle_unstake(&mut self.nft_pool.accumulator, reward_rate, &stake_account, stake_account.staked_amt, annual_percentage_rate, paused, current)?;
+
+            emit!(Unstaked {
+                owner: self.user.key(),
+                stake_account: stake_key,
+                asset_kind: AssetKind::Nft,
+                mint: mint_key,
+                amount: stake_account.staked_amt,
+                seed: stake_account.seed,
+                lock_period: stake_account.lock_period,
+                staked_at: stake_account.staked_at,
+                unstaked_at: current,
+                remaining: 0,
+                rewards_forfeited: paused,
+            });
+
+            reward_amount = reward_amount.checked_add(reward).ok_or(ErrorCode::OverFlow)?;
+
+            stake_account.close(self.user.to_account_info())?;
+        }
+
+        self.user_account.nft_staked_amount = self.user_account.nft_staked_amount.checked_sub(count as u64).ok_or(ErrorCode::UnderFlow)?;
+
+        self.reward_user(reward_amount)?;
+
+        Ok(())
+    }
+
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: Pubkey::default(), // a batch payout covers several stake accounts
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+}
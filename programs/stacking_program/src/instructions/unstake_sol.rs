@@ -1,7 +1,7 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{ mint_to, Mint, MintTo, Token, TokenAccount};
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+use anchor_spl::{token::Token, token_interface::{Mint, TokenAccount}};
 
-use crate::{error::ErrorCode, StakeAccount, StateConfig, UserAccount};
+use crate::{error::ErrorCode, events::{NativeStakeWithdrawn, Unstaked}, native_stake::{native_surplus, stake_account_rent, withdraw_native_stake}, rewards::{settle_unstake, RewardPayout}, AssetKind, StakeAccount, StateConfig, UserAccount};
 
 #[derive(Accounts)]
 pub struct UnStakeSOl <'info> {
@@ -11,39 +11,45 @@ pub struct UnStakeSOl <'info> {
 
     #[account(
         mut,
-        seeds = [b"rewards", config.key().as_ref()],
-        bump = config.rewards_bump,
-        mint::authority = config,
+        address = config.reward_mint,
     )]
-    pub reward_mint: Account<'info, Mint>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = reward_mint,
         associated_token::authority = user,
     )]
-    pub user_reward_ata: Account<'info, TokenAccount>,
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+    )]
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        close = user,
         seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()], // seed so that user can stake multiple ammounts
         bump = stake_account.bump,
     )]
     pub stake_account: Account<'info, StakeAccount>,
     
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump,
     )]
     pub config: Account<'info, StateConfig>,
 
-    // #[account(
-    //     mut,
-    //     seeds = [b"vault", stake_account.key().as_ref()],
-    //     bump = stake_account.vault_bump,
-    // )]
-    // pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", stake_account.key().as_ref()],
+        bump = stake_account.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
 
     #[account(
         mut,
@@ -52,83 +58,173 @@ pub struct UnStakeSOl <'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
+    // delegated positions only
+    /// CHECK: native stake account created by `delegate_stake_sol`
+    #[account(
+        mut,
+        seeds = [b"native_stake", stake_account.key().as_ref()],
+        bump = stake_account.native_stake_bump,
+    )]
+    pub native_stake: Option<UncheckedAccount<'info>>,
+
+    pub clock: Option<Sysvar<'info, Clock>>,
+
+    /// CHECK: stake history sysvar
+    #[account(address = solana_sdk_ids::sysvar::stake_history::ID)]
+    pub stake_history: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: native stake program
+    #[account(address = solana_sdk_ids::stake::ID)]
+    pub stake_program: Option<UncheckedAccount<'info>>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 } 
 
 impl <'info> UnStakeSOl <'info> {
-    pub fn unstake_sol(&mut self) -> Result<()> {
+    // Withdraws `amount` of the position, the stake account is closed once nothing is left staked.
+    // A delegated position has to be deactivated with `deactivate_stake_sol` first, once the cooldown
+    // epoch has passed this pulls the native stake back into the vault and unstakes from it.
+    pub fn unstake_sol(&mut self, amount: u64) -> Result<()> {
+
+        let staked_amt = self.stake_account.staked_amt;
+        require!(amount > 0 && amount <= staked_amt, ErrorCode::InvalidUnstakeAmount);
+        let remaining = staked_amt - amount;
 
         let staked_at = self.stake_account.staked_at;
         let current = Clock::get()?.unix_timestamp;
         let time_passed = current.checked_sub(staked_at).unwrap();
 
-        require!( time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
-
-        // let seeds = &[
-        //     b"vault",
-        //     self.stake_account.to_account_info().key.as_ref(),
-        //     &[self.stake_account.vault_bump],
-        // ];
-
-        // let signer_seeds = &[&seeds[..]];
+        let paused = self.config.is_paused(AssetKind::Sol);
 
-        // let cpi_program = self.system_program.to_account_info();
-        // let cpi_accounts = Transfer {
-        //     from: self.vault.to_account_info(),
-        //     to: self.user.to_account_info(),
-        // };
+        // while paused the lock is waived so users can always recover their principal
+        require!(paused || time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
 
-        // let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        if self.stake_account.stake_activation_epoch.is_some() {
+            self.release_native_stake()?;
+        }
 
-        // transfer(cpi_ctx, self.vault.lamports())?;
+        let stake_key = self.stake_account.key();
+        let seeds = &[
+            b"vault",
+            stake_key.as_ref(),
+            &[self.stake_account.vault_bump],
+        ];
 
-        let points_u64 = u64::try_from(self.config.points_per_sol_stake).or(Err(ErrorCode::OverFlow))?;
-        let time_passed_u64 = u64::try_from(time_passed).or(Err(ErrorCode::OverFlow))?;
+        let signer_seeds = &[&seeds[..]];
 
-        let mut reward_amount: u64 = points_u64.checked_mul(time_passed_u64).ok_or(ErrorCode::OverFlow)?;
+        let cpi_program = self.system_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.user.to_account_info(),
+        };
 
-        if self.stake_account.locked_stackers {
-            let annual_percentage_rate_u64 = u64::try_from(self.config.annaul_percentage_rate).or(Err(ErrorCode::OverFlow))?;
-            let yield_time_u64 = u64::try_from(self.stake_account.lock_period).or(Err(ErrorCode::OverFlow))?;
-            let yield_reward = yield_time_u64.checked_mul(points_u64).ok_or(ErrorCode::OverFlow)?;
-            let product: u64 = yield_reward.checked_mul(annual_percentage_rate_u64).ok_or(ErrorCode::OverFlow)?;
-            let yield_amt: u64 = product.checked_div(10_000u64).ok_or(ErrorCode::OverFlow)?;
-            reward_amount = reward_amount.checked_add(yield_amt).ok_or(ErrorCode::OverFlow)?;
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        // a full exit also returns the vault's rent reserve, which empties it
+        let withdraw_amount = if remaining == 0 { self.vault.lamports() } else { amount };
+
+        transfer(cpi_ctx, withdraw_amount)?;
+
+        self.user_account.sol_staked_amount = self.user_account.sol_staked_amount.checked_sub(amount).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.config.sol_reward_rate;
+        let annual_percentage_rate = self.config.annaul_percentage_rate;
+        let (reward_amount, reward_debt) = settle_unstake(&mut self.config.sol_accumulator, reward_rate, &self.stake_account, amount, annual_percentage_rate, paused, current)?;
+
+        emit!(Unstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Sol,
+            mint: self.stake_account.mint,
+            amount,
+            seed: self.stake_account.seed,
+            lock_period: self.stake_account.lock_period,
+            staked_at,
+            unstaked_at: current,
+            remaining,
+            rewards_forfeited: paused,
+        });
+
+        if remaining == 0 {
+            self.stake_account.close(self.user.to_account_info())?;
+        } else {
+            self.stake_account.staked_amt = remaining;
+            self.stake_account.reward_debt = reward_debt;
         }
 
-        self.user_account.sol_staked_amount = self.user_account.sol_staked_amount.checked_sub(self.stake_account.staked_amt).ok_or(ErrorCode::UnderFlow)?;
-
         self.reward_user(reward_amount)?;
 
         Ok(())
 
     }
 
-    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
+    // Withdraws the cooled down native stake into the vault and passes the native staking yield
+    // (plus the stake account's rent) to the user.
+    pub fn release_native_stake(&mut self) -> Result<()> {
+        let deactivation_epoch = self.stake_account.stake_deactivation_epoch.ok_or(ErrorCode::StakeDeactivationRequired)?;
+        let native_stake = self.native_stake.as_ref().ok_or(ErrorCode::MissingNativeStakeAccounts)?.to_account_info();
+        let clock = self.clock.as_ref().ok_or(ErrorCode::MissingNativeStakeAccounts)?;
+        require!(self.stake_program.is_some(), ErrorCode::MissingNativeStakeAccounts);
+
+        let stake_key = self.stake_account.key();
+        let vault_seeds = &[
+            b"vault",
+            stake_key.as_ref(),
+            &[self.stake_account.vault_bump],
+        ];
 
-        let cpi_accounts = MintTo {
-            mint: self.reward_mint.to_account_info(),
-            to: self.user_reward_ata.to_account_info(),
-            authority: self.config.to_account_info()
-        };
+        require!(clock.epoch > deactivation_epoch, ErrorCode::StakeNotDeactivated);
 
-        let seeds = &[
-            &b"config"[..],
-            &[self.config.bump]
-        ];
+        let stake_history = self.stake_history.as_ref().ok_or(ErrorCode::MissingNativeStakeAccounts)?;
 
-        let signer_seeds = &[&seeds[..]];
+        let withdrawn = withdraw_native_stake(
+            native_stake,
+            self.vault.to_account_info(),
+            clock.to_account_info(),
+            stake_history.to_account_info(),
+            vault_seeds,
+        )?;
+
+        let principal = self.stake_account.staked_amt;
+        let (surplus, native_yield, shortfall) = native_surplus(withdrawn, principal, stake_account_rent()?);
+
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.user.to_account_info(),
+        };
 
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        let signer_seeds = &[&vault_seeds[..]];
 
-        mint_to(ctx, amount)?;
+        let cpi_ctx = CpiContext::new_with_signer(self.system_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, surplus)?;
 
-        self.user_account.points = self.user_account.points.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+        self.stake_account.stake_activation_epoch = None;
+        self.stake_account.stake_deactivation_epoch = None;
+
+        emit!(NativeStakeWithdrawn {
+            owner: self.user.key(),
+            stake_account: stake_key,
+            principal,
+            native_yield,
+            shortfall,
+        });
 
         Ok(())
     }
 
+    pub fn reward_user(&mut self, amount: u64) -> Result<()> {
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.token_program.to_account_info(),
+        }.pay(amount)
+    }
+
 
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{close_account, mint_to, transfer_checked, CloseAccount, Mint, MintTo, Token, TokenAccount, TransferChecked}};
-use crate::{error::ErrorCode, StakeAccount, StateConfig, UserAccount};
+use anchor_spl::{associated_token::AssociatedToken, token::Token, token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked}};
+use crate::{error::ErrorCode, events::Unstaked, rewards::{settle_unstake, RewardPayout}, token_vault::harvest_withheld_fees, AssetKind, SplPool, StakeAccount, StateConfig, UserAccount};
 
 #[derive(Accounts)]
 pub struct UnStakeSPL <'info> {
@@ -8,39 +8,53 @@ pub struct UnStakeSPL <'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    #[account(
+        mut, // Token-2022 fees withheld in the vault are harvested to the mint before it is closed
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub mint_ata: Account<'info, TokenAccount>,
+    pub mint_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [b"rewards", config.key().as_ref()],
-        bump = config.rewards_bump,
-        mint::authority = config,
+        address = config.reward_mint,
+        mint::token_program = reward_token_program,
     )]
-    pub reward_mint: Account<'info, Mint>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = reward_mint,
         associated_token::authority = user,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // RewardMode::Vault
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+        associated_token::token_program = reward_token_program,
     )]
-    pub user_reward_ata: Account<'info, TokenAccount>,
+    pub reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        close = user,
         seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), mint.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()], // seed so that user can stake multiple ammounts
         bump = stake_account.bump
     )]
     pub stake_account: Account<'info, StakeAccount>,
     
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump,
     )]
@@ -48,11 +62,18 @@ pub struct UnStakeSPL <'info> {
 
     #[account(
         mut,
-        close = user,
+        seeds = [b"pool", config.key().as_ref(), mint.key().as_ref()],
+        bump = spl_pool.bump,
+    )]
+    pub spl_pool: Account<'info, SplPool>,
+
+    #[account(
+        mut,
         associated_token::mint = mint,
         associated_token::authority = stake_account,
+        associated_token::token_program = token_program,
     )]
-    pub vault_ata: Account<'info, TokenAccount>,
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
 
 
     #[account(
@@ -62,21 +83,30 @@ pub struct UnStakeSPL <'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>, // classic SPL Token or Token-2022, for the staked mint
+    pub reward_token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>
 } 
 
 impl <'info> UnStakeSPL <'info> {
 
-    pub fn unstake_spl(&mut self) -> Result<()> {
+    // withdraws `amount` of the position, the vault and stake account are closed once nothing is left staked
+    pub fn unstake_spl(&mut self, amount: u64) -> Result<()> {
+
+        let staked_amt = self.stake_account.staked_amt;
+        require!(amount > 0 && amount <= staked_amt, ErrorCode::InvalidUnstakeAmount);
+        let remaining = staked_amt - amount;
 
         let staked_at = self.stake_account.staked_at;
         let current = Clock::get()?.unix_timestamp;
 
         let time_passed = current.checked_sub(staked_at).unwrap();
 
-        require!( time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
+        let paused = self.config.is_paused(AssetKind::Spl);
+
+        // while paused the lock is waived so users can always recover their principal
+        require!(paused || time_passed >= self.stake_account.lock_period, ErrorCode::FreezePeriodeNotPassed);
 
 
         let seeds = &[
@@ -101,62 +131,67 @@ impl <'info> UnStakeSPL <'info> {
 
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
-        transfer_checked(cpi_ctx, self.vault_ata.amount, self.mint.decimals)?;
+        // a full exit sweeps the whole vault so stray deposits cannot block closing it
+        let transfer_amount = if remaining == 0 { self.vault_ata.amount } else { amount };
 
-        
-        let close_accounts = CloseAccount {
-            account: self.vault_ata.to_account_info(),
-            destination: self.user.to_account_info(),
-            authority: self.stake_account.to_account_info()
-        };
-        
-        let close_cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer_seeds);
-        
-        close_account(close_cpi_ctx)?;
+        transfer_checked(cpi_ctx, transfer_amount, self.mint.decimals)?;
+
+        if remaining == 0 {
+            harvest_withheld_fees(self.token_program.to_account_info(), self.mint.to_account_info(), self.vault_ata.to_account_info())?;
 
-        let points_u64 = u64::try_from(self.config.points_per_spl_stake).or(Err(ErrorCode::OverFlow))?;
-        let time_passed_u64 = u64::try_from(time_passed).or(Err(ErrorCode::OverFlow))?;
+            let close_accounts = CloseAccount {
+                account: self.vault_ata.to_account_info(),
+                destination: self.user.to_account_info(),
+                authority: self.stake_account.to_account_info()
+            };
 
-        let mut reward_amount: u64 = points_u64.checked_mul(time_passed_u64).ok_or(ErrorCode::OverFlow)?;
+            let close_cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer_seeds);
 
-        if self.stake_account.locked_stackers {
-            let annual_percentage_rate_u64 = u64::try_from(self.config.annaul_percentage_rate).or(Err(ErrorCode::OverFlow))?;
-            let yield_time_u64 = u64::try_from(self.stake_account.lock_period).or(Err(ErrorCode::OverFlow))?;
-            let yield_reward = yield_time_u64.checked_mul(points_u64).ok_or(ErrorCode::OverFlow)?;
-            let product: u64 = yield_reward.checked_mul(annual_percentage_rate_u64).ok_or(ErrorCode::OverFlow)?;
-            let yield_amt: u64 = product.checked_div(10_000u64).ok_or(ErrorCode::OverFlow)?;
-            reward_amount = reward_amount.checked_add(yield_amt).ok_or(ErrorCode::OverFlow)?;
+            close_account(close_cpi_ctx)?;
         }
-        
-        self.user_account.spl_staked_amount = self.user_account.spl_staked_amount.checked_add(self.vault_ata.amount).ok_or(ErrorCode::OverFlow)?;
+
+        self.user_account.spl_staked_amount = self.user_account.spl_staked_amount.checked_sub(amount).ok_or(ErrorCode::UnderFlow)?;
+
+        let reward_rate = self.spl_pool.reward_rate;
+        let (reward_amount, reward_debt) = settle_unstake(&mut self.spl_pool.accumulator, reward_rate, &self.stake_account, amount, self.config.annaul_percentage_rate, paused, current)?;
+
+        emit!(Unstaked {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            asset_kind: AssetKind::Spl,
+            mint: self.stake_account.mint,
+            amount,
+            seed: self.stake_account.seed,
+            lock_period: self.stake_account.lock_period,
+            staked_at,
+            unstaked_at: current,
+            remaining,
+            rewards_forfeited: paused,
+        });
+
+        if remaining == 0 {
+            self.stake_account.close(self.user.to_account_info())?;
+        } else {
+            self.stake_account.staked_amt = remaining;
+            self.stake_account.reward_debt = reward_debt;
+        }
+
         self.reward_user(reward_amount)?;
 
         Ok(())
     }
 
     pub fn reward_user(&mut self, amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = MintTo {
-            mint: self.reward_mint.to_account_info(),
-            to: self.user_reward_ata.to_account_info(),
-            authority: self.config.to_account_info()
-        };
-
-        let seeds = &[
-            &b"config"[..],
-            &[self.config.bump]
-        ];
-
-        let signer_seeds = &[&seeds[..]];
-
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        mint_to(ctx, amount)?;
-
-        self.user_account.points = self.user_account.points.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
-
-        Ok(())
+        RewardPayout {
+            owner: self.user.key(),
+            stake_account: self.stake_account.key(),
+            config: &mut self.config,
+            user_account: &mut self.user_account,
+            reward_mint: &self.reward_mint,
+            user_reward_ata: &self.user_reward_ata,
+            reward_vault: self.reward_vault.as_ref(),
+            token_program: self.reward_token_program.to_account_info(),
+        }.pay(amount)
     }
 
 
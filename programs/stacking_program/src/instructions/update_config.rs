@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::ConfigUpdated, StateConfig};
+
+#[derive(Accounts)]
+pub struct UpdateConfig <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+}
+
+impl <'info> UpdateConfig <'info> {
+    pub fn update_config(
+        &mut self,
+        sol_reward_rate: Option<u64>,
+        min_freeze_period: Option<i64>,
+        annaul_percentage_rate: Option<u16>,
+        max_total_emission: Option<u64>,
+        epoch_emission_budget: Option<u64>,
+        early_unstake_penalty_bps: Option<u16>,
+    ) -> Result<()> {
+        let old = self.config.clone();
+
+        let config = &mut self.config;
+
+        // price the time elapsed so far at the old SOL rate before it changes
+        config.sol_accumulator.update(old.sol_reward_rate, Clock::get()?.unix_timestamp)?;
+
+        config.sol_reward_rate = sol_reward_rate.unwrap_or(old.sol_reward_rate);
+        config.min_freeze_period = min_freeze_period.unwrap_or(old.min_freeze_period);
+        config.annaul_percentage_rate = annaul_percentage_rate.unwrap_or(old.annaul_percentage_rate);
+        config.max_total_emission = max_total_emission.unwrap_or(old.max_total_emission);
+        config.epoch_emission_budget = epoch_emission_budget.unwrap_or(old.epoch_emission_budget);
+        config.early_unstake_penalty_bps = early_unstake_penalty_bps.unwrap_or(old.early_unstake_penalty_bps);
+
+        config.validate()?;
+
+        emit!(ConfigUpdated {
+            admin: self.admin.key(),
+            old_sol_reward_rate: old.sol_reward_rate,
+            new_sol_reward_rate: config.sol_reward_rate,
+            old_min_freeze_period: old.min_freeze_period,
+            new_min_freeze_period: config.min_freeze_period,
+            old_annaul_percentage_rate: old.annaul_percentage_rate,
+            new_annaul_percentage_rate: config.annaul_percentage_rate,
+            old_max_total_emission: old.max_total_emission,
+            new_max_total_emission: config.max_total_emission,
+            old_epoch_emission_budget: old.epoch_emission_budget,
+            new_epoch_emission_budget: config.epoch_emission_budget,
+            old_early_unstake_penalty_bps: old.early_unstake_penalty_bps,
+            new_early_unstake_penalty_bps: config.early_unstake_penalty_bps,
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::LockTiersUpdated, LockTier, LockTiers, StateConfig};
+
+#[derive(Accounts)]
+pub struct UpdateLockTiers <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_tiers", config.key().as_ref()],
+        bump = lock_tiers.bump,
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+}
+
+impl <'info> UpdateLockTiers <'info> {
+    // existing stakes keep the multiplier they snapped to when they were created
+    pub fn update_lock_tiers(&mut self, tiers: Vec<LockTier>) -> Result<()> {
+        LockTiers::validate(&tiers)?;
+
+        self.lock_tiers.tiers = tiers.clone();
+
+        emit!(LockTiersUpdated {
+            admin: self.admin.key(),
+            tiers,
+        });
+
+        Ok(())
+    }
+}
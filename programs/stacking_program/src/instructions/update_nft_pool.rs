@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::NftPoolUpdated, NftCollectionPool, StateConfig};
+
+#[derive(Accounts)]
+pub struct UpdateNftPool <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_pool", config.key().as_ref(), nft_pool.collection_mint.as_ref()],
+        bump = nft_pool.bump,
+    )]
+    pub nft_pool: Account<'info, NftCollectionPool>,
+}
+
+impl <'info> UpdateNftPool <'info> {
+    pub fn update_nft_pool(&mut self, reward_rate: Option<u64>, allowed: Option<bool>) -> Result<()> {
+        let pool = &mut self.nft_pool;
+
+        // price the time elapsed so far at the old rate before it changes
+        let old_rate = pool.reward_rate;
+        pool.accumulator.update(old_rate, Clock::get()?.unix_timestamp)?;
+
+        pool.reward_rate = reward_rate.unwrap_or(pool.reward_rate);
+        pool.allowed = allowed.unwrap_or(pool.allowed);
+
+        emit!(NftPoolUpdated {
+            admin: self.admin.key(),
+            collection_mint: pool.collection_mint,
+            reward_rate: pool.reward_rate,
+            allowed: pool.allowed,
+        });
+
+        Ok(())
+    }
+}
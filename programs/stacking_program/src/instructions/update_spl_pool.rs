@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::SplPoolUpdated, SplPool, StateConfig};
+
+#[derive(Accounts)]
+pub struct UpdateSplPool <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", config.key().as_ref(), spl_pool.mint.as_ref()],
+        bump = spl_pool.bump,
+    )]
+    pub spl_pool: Account<'info, SplPool>,
+}
+
+impl <'info> UpdateSplPool <'info> {
+    pub fn update_spl_pool(
+        &mut self,
+        reward_rate: Option<u64>,
+        min_stake_amount: Option<u64>,
+        max_stake_amount: Option<u64>,
+        enabled: Option<bool>,
+    ) -> Result<()> {
+        let pool = &mut self.spl_pool;
+
+        // price the time elapsed so far at the old rate before it changes
+        let old_rate = pool.reward_rate;
+        pool.accumulator.update(old_rate, Clock::get()?.unix_timestamp)?;
+
+        pool.reward_rate = reward_rate.unwrap_or(pool.reward_rate);
+        pool.min_stake_amount = min_stake_amount.unwrap_or(pool.min_stake_amount);
+        pool.max_stake_amount = max_stake_amount.unwrap_or(pool.max_stake_amount);
+        pool.enabled = enabled.unwrap_or(pool.enabled);
+
+        require!(pool.min_stake_amount <= pool.max_stake_amount, ErrorCode::InvalidStakeLimits);
+
+        emit!(SplPoolUpdated {
+            admin: self.admin.key(),
+            mint: pool.mint,
+            reward_rate: pool.reward_rate,
+            min_stake_amount: pool.min_stake_amount,
+            max_stake_amount: pool.max_stake_amount,
+            enabled: pool.enabled,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+
+use crate::{error::ErrorCode, events::NativeStakeWithdrawn, native_stake::{native_surplus, stake_account_rent, withdraw_native_stake}, StakeAccount, StateConfig};
+
+#[derive(Accounts)]
+pub struct WithdrawStakeSOl <'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", config.key().as_ref(), user.key().as_ref(), stake_account.seed.to_le_bytes().as_ref()],
+        bump = stake_account.bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", stake_account.key().as_ref()],
+        bump = stake_account.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: native stake account created by `delegate_stake_sol`
+    #[account(
+        mut,
+        seeds = [b"native_stake", stake_account.key().as_ref()],
+        bump = stake_account.native_stake_bump,
+    )]
+    pub native_stake: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK: stake history sysvar
+    #[account(address = solana_sdk_ids::sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: native stake program
+    #[account(address = solana_sdk_ids::stake::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> WithdrawStakeSOl <'info> {
+    // Pulls the deactivated native stake back into the vault so the position can be unstaked as usual.
+    // Everything above the principal (native staking yield plus the stake account's rent) goes to the user.
+    pub fn withdraw_stake_sol(&mut self) -> Result<()> {
+        let deactivation_epoch = self.stake_account.stake_deactivation_epoch.ok_or(ErrorCode::StakeNotDeactivated)?;
+        require!(self.clock.epoch > deactivation_epoch, ErrorCode::StakeNotDeactivated);
+
+        let stake_key = self.stake_account.key();
+        let principal = self.stake_account.staked_amt;
+
+        let vault_seeds = &[
+            b"vault",
+            stake_key.as_ref(),
+            &[self.stake_account.vault_bump],
+        ];
+
+        let signer_seeds = &[&vault_seeds[..]];
+
+        let withdrawn = withdraw_native_stake(
+            self.native_stake.to_account_info(),
+            self.vault.to_account_info(),
+            self.clock.to_account_info(),
+            self.stake_history.to_account_info(),
+            vault_seeds,
+        )?;
+
+        let (surplus, native_yield, shortfall) = native_surplus(withdrawn, principal, stake_account_rent()?);
+
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.user.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(self.system_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, surplus)?;
+
+        self.stake_account.stake_activation_epoch = None;
+        self.stake_account.stake_deactivation_epoch = None;
+
+        emit!(NativeStakeWithdrawn {
+            owner: self.user.key(),
+            stake_account: stake_key,
+            principal,
+            native_yield,
+            shortfall,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::TreasuryWithdrawn, StateConfig, Treasury};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasurySOl <'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl <'info> WithdrawTreasurySOl <'info> {
+    // the treasury is program owned, so penalty lamports are moved directly and its rent reserve stays behind
+    pub fn withdraw_treasury_sol(&mut self, amount: u64) -> Result<()> {
+        let treasury = self.treasury.to_account_info();
+
+        let rent_reserve = Rent::get()?.minimum_balance(treasury.data_len());
+        let available = treasury.lamports().checked_sub(rent_reserve).ok_or(ErrorCode::UnderFlow)?;
+
+        require!(amount > 0 && amount <= available, ErrorCode::InsufficientTreasuryFunds);
+
+        treasury.sub_lamports(amount)?;
+        self.admin.add_lamports(amount)?;
+
+        emit!(TreasuryWithdrawn {
+            admin: self.admin.key(),
+            mint: None,
+            amount,
+            remaining: available - amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{error::ErrorCode, events::TreasuryWithdrawn, StateConfig, Treasury};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasurySPL <'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StateConfig>,
+
+    #[account(
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+        token::token_program = token_program,
+    )]
+    pub admin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>, // classic SPL Token or Token-2022, for the penalty mint
+}
+
+impl <'info> WithdrawTreasurySPL <'info> {
+    pub fn withdraw_treasury_spl(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0 && amount <= self.treasury_ata.amount, ErrorCode::InsufficientTreasuryFunds);
+
+        let config_key = self.config.key();
+        let seeds = &[
+            b"treasury",
+            config_key.as_ref(),
+            &[self.treasury.bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: self.treasury_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.admin_ata.to_account_info(),
+            authority: self.treasury.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+        self.treasury_ata.reload()?;
+
+        emit!(TreasuryWithdrawn {
+            admin: self.admin.key(),
+            mint: Some(self.mint.key()),
+            amount,
+            remaining: self.treasury_ata.amount,
+        });
+
+        Ok(())
+    }
+}
@@ -1,10 +1,20 @@
+pub mod compressed_nft;
 pub mod constants;
+pub mod core_asset;
 pub mod error;
+pub mod events;
 pub mod instructions;
+pub mod native_stake;
+pub mod nft_lock;
+pub mod pda_account;
+pub mod reward_math;
+pub mod rewards;
 pub mod state;
+pub mod token_vault;
 
 use anchor_lang::prelude::*;
 
+pub use compressed_nft::MetadataArgs;
 pub use constants::*;
 pub use instructions::*;
 pub use state::*;
@@ -17,13 +27,93 @@ pub mod stacking_program {
 
     pub fn initialize_config(
         ctx: Context<InitializeConfig>, 
-        points_per_nft_stake: u8, // 1000/10000
-        points_per_sol_stake: u8, // 100/10000
-        points_per_spl_stake: u8, // 10/10000  
+        sol_reward_rate: u64, // tokens per second shared by all SOL stakers
         min_freeze_period: i64,
         annaul_percentage_rate: u16,
+        reward_mode: RewardMode,
     ) -> Result<()> {
-        ctx.accounts.initialize_config(points_per_nft_stake, points_per_spl_stake, points_per_sol_stake, min_freeze_period, annaul_percentage_rate, &ctx.bumps)
+        ctx.accounts.initialize_config(sol_reward_rate, min_freeze_period, annaul_percentage_rate, reward_mode, &ctx.bumps)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        sol_reward_rate: Option<u64>,
+        min_freeze_period: Option<i64>,
+        annaul_percentage_rate: Option<u16>,
+        max_total_emission: Option<u64>,
+        epoch_emission_budget: Option<u64>,
+        early_unstake_penalty_bps: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.update_config(
+            sol_reward_rate,
+            min_freeze_period,
+            annaul_percentage_rate,
+            max_total_emission,
+            epoch_emission_budget,
+            early_unstake_penalty_bps,
+        )
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.propose_admin(new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        ctx.accounts.accept_admin()
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+        ctx.accounts.set_paused(paused)
+    }
+
+    pub fn create_spl_pool(ctx: Context<CreateSplPool>, reward_rate: u64, min_stake_amount: u64, max_stake_amount: u64) -> Result<()> {
+        ctx.accounts.create_spl_pool(reward_rate, min_stake_amount, max_stake_amount, &ctx.bumps)
+    }
+
+    pub fn update_spl_pool(
+        ctx: Context<UpdateSplPool>,
+        reward_rate: Option<u64>,
+        min_stake_amount: Option<u64>,
+        max_stake_amount: Option<u64>,
+        enabled: Option<bool>,
+    ) -> Result<()> {
+        ctx.accounts.update_spl_pool(reward_rate, min_stake_amount, max_stake_amount, enabled)
+    }
+
+    pub fn create_nft_pool(ctx: Context<CreateNftPool>, reward_rate: u64) -> Result<()> {
+        ctx.accounts.create_nft_pool(reward_rate, &ctx.bumps)
+    }
+
+    pub fn create_core_pool(ctx: Context<CreateCorePool>, reward_rate: u64) -> Result<()> {
+        ctx.accounts.create_core_pool(reward_rate, &ctx.bumps)
+    }
+
+    pub fn update_nft_pool(ctx: Context<UpdateNftPool>, reward_rate: Option<u64>, allowed: Option<bool>) -> Result<()> {
+        ctx.accounts.update_nft_pool(reward_rate, allowed)
+    }
+
+    pub fn create_lock_tiers(ctx: Context<CreateLockTiers>, tiers: Vec<LockTier>) -> Result<()> {
+        ctx.accounts.create_lock_tiers(tiers, &ctx.bumps)
+    }
+
+    pub fn update_lock_tiers(ctx: Context<UpdateLockTiers>, tiers: Vec<LockTier>) -> Result<()> {
+        ctx.accounts.update_lock_tiers(tiers)
+    }
+
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        ctx.accounts.fund_rewards(amount)
+    }
+
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        ctx.accounts.initialize_treasury(&ctx.bumps)
+    }
+
+    pub fn withdraw_treasury_sol(ctx: Context<WithdrawTreasurySOl>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_treasury_sol(amount)
+    }
+
+    pub fn withdraw_treasury_spl(ctx: Context<WithdrawTreasurySPL>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_treasury_spl(amount)
     }
 
     pub fn initialize_user(ctx: Context<InitializeUser>) -> Result<()> {
@@ -34,6 +124,18 @@ pub mod stacking_program {
         ctx.accounts.stake_nft(seed, locked_stackers, lock_period, &ctx.bumps)
     }
 
+    pub fn stake_nft_batch<'info>(ctx: Context<'_, '_, 'info, 'info, StakeNFTBatch<'info>>, seeds: Vec<u64>, locked_stackers: bool, lock_period: i64) -> Result<()> {
+        ctx.accounts.stake_nft_batch(seeds, locked_stackers, lock_period, ctx.remaining_accounts)
+    }
+
+    pub fn stake_core_asset(ctx: Context<StakeCoreAsset>, seed: u64, locked_stackers: bool, lock_period: i64) -> Result<()> {
+        ctx.accounts.stake_core_asset(seed, locked_stackers, lock_period, &ctx.bumps)
+    }
+
+    pub fn stake_cnft<'info>(ctx: Context<'_, '_, 'info, 'info, StakeCnft<'info>>, seed: u64, index: u32, locked_stackers: bool, lock_period: i64, root: [u8; 32], metadata: MetadataArgs) -> Result<()> {
+        ctx.accounts.stake_cnft(seed, index, locked_stackers, lock_period, root, metadata, ctx.remaining_accounts, &ctx.bumps)
+    }
+
     pub fn stake_sol(ctx: Context<StakeSOl>, seed: u64, amount: u64, locked_stackers: bool, lock_period: i64, ) -> Result<()> {
         ctx.accounts.stake_sol(seed, amount, locked_stackers, lock_period, &ctx.bumps)
     }
@@ -42,16 +144,76 @@ pub mod stacking_program {
         ctx.accounts.stake_spl(seed, amount, locked_stackers, lock_period, &ctx.bumps)
     }
 
+    pub fn increase_stake_sol(ctx: Context<IncreaseStakeSOl>, amount: u64, lock_period: Option<i64>) -> Result<()> {
+        ctx.accounts.increase_stake_sol(amount, lock_period)
+    }
+
+    pub fn increase_stake_spl(ctx: Context<IncreaseStakeSPL>, amount: u64, lock_period: Option<i64>) -> Result<()> {
+        ctx.accounts.increase_stake_spl(amount, lock_period)
+    }
+
+    pub fn set_validator(ctx: Context<SetValidator>) -> Result<()> {
+        ctx.accounts.set_validator()
+    }
+
+    pub fn delegate_stake_sol(ctx: Context<DelegateStakeSOl>) -> Result<()> {
+        ctx.accounts.delegate_stake_sol(&ctx.bumps)
+    }
+
+    pub fn deactivate_stake_sol(ctx: Context<DeactivateStakeSOl>) -> Result<()> {
+        ctx.accounts.deactivate_stake_sol()
+    }
+
+    pub fn withdraw_stake_sol(ctx: Context<WithdrawStakeSOl>) -> Result<()> {
+        ctx.accounts.withdraw_stake_sol()
+    }
+
     pub fn unstake_nft(ctx: Context<UnStakeNFT>) -> Result<()> {
         ctx.accounts.unstake_nft()
     }
 
-    pub fn unstake_sol(ctx: Context<UnStakeSOl>) -> Result<()> {
-        ctx.accounts.unstake_sol()
+    pub fn unstake_nft_batch<'info>(ctx: Context<'_, '_, 'info, 'info, UnStakeNFTBatch<'info>>) -> Result<()> {
+        ctx.accounts.unstake_nft_batch(ctx.remaining_accounts)
+    }
+
+    pub fn unstake_core_asset(ctx: Context<UnStakeCoreAsset>) -> Result<()> {
+        ctx.accounts.unstake_core_asset()
+    }
+
+    pub fn unstake_cnft<'info>(ctx: Context<'_, '_, 'info, 'info, UnStakeCnft<'info>>, root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.unstake_cnft(root, data_hash, creator_hash, ctx.remaining_accounts)
+    }
+
+    pub fn unstake_sol(ctx: Context<UnStakeSOl>, amount: u64) -> Result<()> {
+        ctx.accounts.unstake_sol(amount)
+    }
+
+    pub fn unstake_spl(ctx: Context<UnStakeSPL>, amount: u64) -> Result<()> {
+        ctx.accounts.unstake_spl(amount)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        ctx.accounts.claim_rewards()
+    }
+
+    pub fn set_compounding(ctx: Context<SetCompounding>, enabled: bool) -> Result<()> {
+        ctx.accounts.set_compounding(enabled)
+    }
+
+    pub fn compound(ctx: Context<Compound>) -> Result<()> {
+        ctx.accounts.compound()
+    }
+
+    pub fn emergency_unstake_nft(ctx: Context<EmergencyUnStakeNFT>) -> Result<()> {
+        ctx.accounts.emergency_unstake_nft()
+    }
+
+    pub fn emergency_unstake_sol(ctx: Context<EmergencyUnStakeSOl>) -> Result<()> {
+        ctx.accounts.emergency_unstake_sol()
     }
 
-    pub fn unstake_spl(ctx: Context<UnStakeSPL>) -> Result<()> {
-        ctx.accounts.unstake_spl()
+    pub fn emergency_unstake_spl(ctx: Context<EmergencyUnStakeSPL>) -> Result<()> {
+        ctx.accounts.emergency_unstake_spl()
     }
 
 }
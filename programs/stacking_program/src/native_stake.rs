@@ -0,0 +1,60 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use solana_stake_interface::{instruction as stake_instruction, state::StakeStateV2};
+
+// starts the cooldown of a delegated position, the vault is the stake and withdraw authority
+pub fn deactivate_native_stake<'info>(native_stake: AccountInfo<'info>, clock: AccountInfo<'info>, vault: AccountInfo<'info>, vault_seeds: &[&[u8]]) -> Result<()> {
+    invoke_signed(
+        &stake_instruction::deactivate_stake(native_stake.key, vault.key),
+        &[native_stake, clock, vault],
+        &[vault_seeds],
+    )?;
+
+    Ok(())
+}
+
+// drains the deactivated native stake account back into the vault, returns the lamports withdrawn
+pub fn withdraw_native_stake<'info>(native_stake: AccountInfo<'info>, vault: AccountInfo<'info>, clock: AccountInfo<'info>, stake_history: AccountInfo<'info>, vault_seeds: &[&[u8]]) -> Result<u64> {
+    let withdrawn = native_stake.lamports();
+
+    invoke_signed(
+        &stake_instruction::withdraw(native_stake.key, vault.key, vault.key, withdrawn, None),
+        &[native_stake, vault.clone(), clock, stake_history, vault],
+        &[vault_seeds],
+    )?;
+
+    Ok(withdrawn)
+}
+
+// Splits a withdrawal into what belongs to the user on top of the principal, the part of it that is
+// native staking yield rather than the stake account's rent the user paid on delegation, and the
+// principal that did not come back (a slashed validator). Only what was withdrawn stays in the vault.
+pub fn native_surplus(withdrawn: u64, principal: u64, stake_rent: u64) -> (u64, u64, u64) {
+    let surplus = withdrawn.saturating_sub(principal);
+    let shortfall = principal.saturating_sub(withdrawn);
+
+    (surplus, surplus.saturating_sub(stake_rent), shortfall)
+}
+
+pub fn stake_account_rent() -> Result<u64> {
+    Ok(Rent::get()?.minimum_balance(StakeStateV2::size_of()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surplus_is_yield_plus_rent() {
+        assert_eq!(native_surplus(1_000_002_500, 1_000_000_000, 2_000), (2_500, 500, 0));
+    }
+
+    #[test]
+    fn rent_alone_is_no_yield() {
+        assert_eq!(native_surplus(1_000_002_000, 1_000_000_000, 2_000), (2_000, 0, 0));
+    }
+
+    #[test]
+    fn slashed_principal_is_a_shortfall_not_an_error() {
+        assert_eq!(native_surplus(999_999_000, 1_000_000_000, 2_000), (0, 0, 1_000));
+    }
+}
@@ -0,0 +1,323 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::{
+            accounts::{MasterEdition, Metadata},
+            instructions::{
+                DelegateStakingV1Cpi, DelegateStakingV1CpiAccounts, DelegateStakingV1InstructionArgs,
+                FreezeDelegatedAccountCpi, FreezeDelegatedAccountCpiAccounts,
+                LockV1Cpi, LockV1CpiAccounts, LockV1InstructionArgs,
+                RevokeStakingV1Cpi, RevokeStakingV1CpiAccounts,
+                ThawDelegatedAccountCpi, ThawDelegatedAccountCpiAccounts,
+                UnlockV1Cpi, UnlockV1CpiAccounts, UnlockV1InstructionArgs,
+            },
+            types::TokenStandard,
+        },
+        MetadataAccount,
+    },
+    token::{approve, close_account, revoke, transfer_checked, Approve, CloseAccount, Mint, Revoke, TokenAccount, TransferChecked},
+};
+
+use crate::error::ErrorCode;
+
+pub fn is_programmable(metadata: &MetadataAccount) -> bool {
+    matches!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible) | Some(TokenStandard::ProgrammableNonFungibleEdition)
+    )
+}
+
+// Freezing goes through the edition, which must be the mint's freeze authority. Print editions,
+// plain SPL NFTs and anything else whose freeze authority is elsewhere can't be frozen.
+pub fn can_freeze(mint: &Mint, edition: &AccountInfo) -> bool {
+    mint.freeze_authority == Some(edition.key()).into() && !edition.data_is_empty()
+}
+
+// Keeps a staked NFT in the owner's wallet while the stake account holds it. Legacy NFTs are
+// approved to the stake account and frozen, programmable NFTs can't be frozen through the token
+// program so they get a Token Metadata staking delegate and a `Lock` on their token record instead.
+// NFTs that can't be frozen at all are escrowed in the stake account's vault ATA like SPL stakes.
+pub struct NftLock<'info> {
+    pub owner: AccountInfo<'info>,
+    // the stake account PDA, delegate of the token while staked
+    pub delegate: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub edition: AccountInfo<'info>,
+    pub programmable: bool,
+    pub escrowed: bool,
+    // only used by escrowed NFTs, the stake account's ATA of `mint`
+    pub vault: Option<AccountInfo<'info>>,
+    // only used by programmable NFTs
+    pub token_record: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub sysvar_instructions: Option<AccountInfo<'info>>,
+    pub token_program: AccountInfo<'info>,
+    pub metadata_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> NftLock<'info> {
+    pub fn lock(&self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        if self.escrowed {
+            let vault = self.vault.as_ref().ok_or(ErrorCode::MissingNftVault)?;
+
+            let cpi_accounts = TransferChecked {
+                from: self.token_account.clone(),
+                mint: self.mint.clone(),
+                to: vault.clone(),
+                authority: self.owner.clone(),
+            };
+
+            transfer_checked(CpiContext::new(self.token_program.clone(), cpi_accounts), 1, 0)?;
+
+            return Ok(());
+        }
+
+        if !self.programmable {
+            let cpi_accounts = Approve {
+                to: self.token_account.clone(),
+                delegate: self.delegate.clone(),
+                authority: self.owner.clone(),
+            };
+
+            approve(CpiContext::new(self.token_program.clone(), cpi_accounts), 1)?;
+
+            FreezeDelegatedAccountCpi::new(
+                &self.metadata_program,
+                FreezeDelegatedAccountCpiAccounts {
+                    delegate: &self.delegate,
+                    token_account: &self.token_account,
+                    edition: &self.edition,
+                    mint: &self.mint,
+                    token_program: &self.token_program,
+                },
+            )
+            .invoke_signed(signer_seeds)?;
+
+            return Ok(());
+        }
+
+        let (token_record, sysvar_instructions) = self.programmable_accounts()?;
+
+        DelegateStakingV1Cpi::new(
+            &self.metadata_program,
+            DelegateStakingV1CpiAccounts {
+                delegate_record: None,
+                delegate: &self.delegate,
+                metadata: &self.metadata,
+                master_edition: Some(&self.edition),
+                token_record: Some(token_record),
+                mint: &self.mint,
+                token: &self.token_account,
+                authority: &self.owner,
+                payer: &self.owner,
+                system_program: &self.system_program,
+                sysvar_instructions,
+                spl_token_program: Some(&self.token_program),
+                authorization_rules_program: self.authorization_rules_program.as_ref(),
+                authorization_rules: self.authorization_rules.as_ref(),
+            },
+            DelegateStakingV1InstructionArgs {
+                amount: 1,
+                authorization_data: None,
+            },
+        )
+        .invoke()?;
+
+        LockV1Cpi::new(
+            &self.metadata_program,
+            LockV1CpiAccounts {
+                authority: &self.delegate,
+                token_owner: Some(&self.owner),
+                token: &self.token_account,
+                mint: &self.mint,
+                metadata: &self.metadata,
+                edition: Some(&self.edition),
+                token_record: Some(token_record),
+                payer: &self.owner,
+                system_program: &self.system_program,
+                sysvar_instructions,
+                spl_token_program: Some(&self.token_program),
+                authorization_rules_program: self.authorization_rules_program.as_ref(),
+                authorization_rules: self.authorization_rules.as_ref(),
+            },
+            LockV1InstructionArgs {
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+
+    pub fn unlock(&self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        if self.escrowed {
+            let vault = self.vault.as_ref().ok_or(ErrorCode::MissingNftVault)?;
+
+            let cpi_accounts = TransferChecked {
+                from: vault.clone(),
+                mint: self.mint.clone(),
+                to: self.token_account.clone(),
+                authority: self.delegate.clone(),
+            };
+
+            transfer_checked(CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signer_seeds), 1, 0)?;
+
+            let close_accounts = CloseAccount {
+                account: vault.clone(),
+                destination: self.owner.clone(),
+                authority: self.delegate.clone(),
+            };
+
+            close_account(CpiContext::new_with_signer(self.token_program.clone(), close_accounts, signer_seeds))?;
+
+            return Ok(());
+        }
+
+        if !self.programmable {
+            ThawDelegatedAccountCpi::new(
+                &self.metadata_program,
+                ThawDelegatedAccountCpiAccounts {
+                    delegate: &self.delegate,
+                    token_account: &self.token_account,
+                    edition: &self.edition,
+                    mint: &self.mint,
+                    token_program: &self.token_program,
+                },
+            )
+            .invoke_signed(signer_seeds)?;
+
+            let cpi_accounts = Revoke {
+                source: self.token_account.clone(),
+                authority: self.owner.clone(),
+            };
+
+            revoke(CpiContext::new(self.token_program.clone(), cpi_accounts))?;
+
+            return Ok(());
+        }
+
+        let (token_record, sysvar_instructions) = self.programmable_accounts()?;
+
+        UnlockV1Cpi::new(
+            &self.metadata_program,
+            UnlockV1CpiAccounts {
+                authority: &self.delegate,
+                token_owner: Some(&self.owner),
+                token: &self.token_account,
+                mint: &self.mint,
+                metadata: &self.metadata,
+                edition: Some(&self.edition),
+                token_record: Some(token_record),
+                payer: &self.owner,
+                system_program: &self.system_program,
+                sysvar_instructions,
+                spl_token_program: Some(&self.token_program),
+                authorization_rules_program: self.authorization_rules_program.as_ref(),
+                authorization_rules: self.authorization_rules.as_ref(),
+            },
+            UnlockV1InstructionArgs {
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        RevokeStakingV1Cpi::new(
+            &self.metadata_program,
+            RevokeStakingV1CpiAccounts {
+                delegate_record: None,
+                delegate: &self.delegate,
+                metadata: &self.metadata,
+                master_edition: Some(&self.edition),
+                token_record: Some(token_record),
+                mint: &self.mint,
+                token: &self.token_account,
+                authority: &self.owner,
+                payer: &self.owner,
+                system_program: &self.system_program,
+                sysvar_instructions,
+                spl_token_program: Some(&self.token_program),
+                authorization_rules_program: self.authorization_rules_program.as_ref(),
+                authorization_rules: self.authorization_rules.as_ref(),
+            },
+        )
+        .invoke()?;
+
+        Ok(())
+    }
+
+    fn programmable_accounts(&self) -> Result<(&AccountInfo<'info>, &AccountInfo<'info>)> {
+        let token_record = self.token_record.as_ref().ok_or(ErrorCode::MissingProgrammableAccounts)?;
+        let sysvar_instructions = self.sysvar_instructions.as_ref().ok_or(ErrorCode::MissingProgrammableAccounts)?;
+
+        Ok((token_record, sysvar_instructions))
+    }
+}
+
+pub const BATCH_ACCOUNTS_PER_NFT: usize = 5;
+
+// One NFT of a batch instruction, read from `remaining_accounts` as
+// `[mint, token_account, metadata, edition, stake_account]`. Batches only take legacy NFTs that
+// can be frozen in place, programmable and escrowed NFTs go through `stake_nft` one at a time.
+pub struct BatchNft<'info> {
+    pub mint: Account<'info, Mint>,
+    pub token_account: Account<'info, TokenAccount>,
+    pub metadata: Account<'info, MetadataAccount>,
+    pub edition: &'info AccountInfo<'info>,
+    pub stake_account: &'info AccountInfo<'info>,
+}
+
+impl<'info> BatchNft<'info> {
+    pub fn load(accounts: &'info [AccountInfo<'info>], owner: &Pubkey, collection_mint: &Pubkey) -> Result<Self> {
+        let [mint, token_account, metadata, edition, stake_account] = accounts else {
+            return err!(ErrorCode::InvalidNftBatch);
+        };
+
+        let mint = Account::<Mint>::try_from(mint)?;
+        require!(mint.supply == 1 && mint.decimals == 0, ErrorCode::NotAnNft);
+
+        let token_account = Account::<TokenAccount>::try_from(token_account)?;
+        require_keys_eq!(token_account.owner, *owner, ErrorCode::InvalidStakeOwner);
+        require!(token_account.mint == mint.key() && token_account.amount == 1, ErrorCode::InvalidNftBatch);
+
+        require_keys_eq!(metadata.key(), Metadata::find_pda(&mint.key()).0, ErrorCode::InvalidNftBatch);
+        let metadata = Account::<MetadataAccount>::try_from(metadata)?;
+        let collection = metadata.collection.as_ref().ok_or(ErrorCode::CollectionNotAllowed)?;
+        require!(collection.verified && collection.key == *collection_mint, ErrorCode::CollectionNotAllowed);
+
+        require_keys_eq!(edition.key(), MasterEdition::find_pda(&mint.key()).0, ErrorCode::InvalidNftBatch);
+        require!(!is_programmable(&metadata) && can_freeze(&mint, edition), ErrorCode::InvalidNftBatch);
+
+        Ok(Self {
+            mint,
+            token_account,
+            metadata,
+            edition,
+            stake_account,
+        })
+    }
+
+    pub fn nft_lock(&self, owner: AccountInfo<'info>, token_program: AccountInfo<'info>, metadata_program: AccountInfo<'info>, system_program: AccountInfo<'info>) -> NftLock<'info> {
+        NftLock {
+            owner,
+            delegate: self.stake_account.clone(),
+            mint: self.mint.to_account_info(),
+            token_account: self.token_account.to_account_info(),
+            metadata: self.metadata.to_account_info(),
+            edition: self.edition.clone(),
+            programmable: false,
+            escrowed: false,
+            vault: None,
+            token_record: None,
+            authorization_rules: None,
+            authorization_rules_program: None,
+            sysvar_instructions: None,
+            token_program,
+            metadata_program,
+            system_program,
+        }
+    }
+}
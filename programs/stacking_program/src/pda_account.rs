@@ -0,0 +1,40 @@
+use anchor_lang::{prelude::*, system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer}};
+
+// Creates a rent exempt PDA the way Anchor's `init` does. `create_account` fails once anyone has sent
+// lamports to the address, so a pre-funded PDA is topped up, allocated and assigned instead.
+pub fn create_pda_account<'info>(payer: AccountInfo<'info>, account: AccountInfo<'info>, system_program: AccountInfo<'info>, space: usize, owner: &Pubkey, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+
+    if current == 0 {
+        let cpi_accounts = CreateAccount {
+            from: payer,
+            to: account,
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(system_program, cpi_accounts, signer_seeds);
+        return create_account(cpi_ctx, rent, space as u64, owner);
+    }
+
+    let top_up = rent.saturating_sub(current);
+    if top_up > 0 {
+        let cpi_accounts = Transfer {
+            from: payer,
+            to: account.clone(),
+        };
+
+        transfer(CpiContext::new(system_program.clone(), cpi_accounts), top_up)?;
+    }
+
+    let cpi_accounts = Allocate {
+        account_to_allocate: account.clone(),
+    };
+
+    allocate(CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds), space as u64)?;
+
+    let cpi_accounts = Assign {
+        account_to_assign: account,
+    };
+
+    assign(CpiContext::new_with_signer(system_program, cpi_accounts, signer_seeds), owner)
+}
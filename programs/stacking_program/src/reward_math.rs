@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, BPS_DENOMINATOR, SECONDS_PER_YEAR};
+
+// Yield of `principal` at `apr_bps` (1 bps = 0.01%) held for `elapsed` seconds:
+//
+//     principal * apr_bps * elapsed / (10_000 * SECONDS_PER_YEAR)
+//
+// Everything is multiplied out in u128 before the single division so no precision is lost
+// to intermediate rounding, and the result is rounded down so the program never pays out
+// more than the exact yield. Non positive `elapsed` earns nothing.
+pub fn apr_yield(principal: u64, apr_bps: u16, elapsed: i64) -> Result<u64> {
+    if elapsed <= 0 {
+        return Ok(0);
+    }
+
+    let numerator = u128::from(principal)
+        .checked_mul(u128::from(apr_bps)).ok_or(ErrorCode::OverFlow)?
+        .checked_mul(elapsed as u128).ok_or(ErrorCode::OverFlow)?;
+
+    let denominator = BPS_DENOMINATOR.checked_mul(SECONDS_PER_YEAR).ok_or(ErrorCode::OverFlow)?;
+
+    let yield_amt = numerator / denominator;
+
+    u64::try_from(yield_amt).or(Err(ErrorCode::OverFlow.into()))
+}
+
+// Scales `amount` by a lock tier multiplier in bps, rounded down like `apr_yield`.
+pub fn apply_multiplier(amount: u64, multiplier_bps: u16) -> Result<u64> {
+    let scaled = u128::from(amount)
+        .checked_mul(u128::from(multiplier_bps)).ok_or(ErrorCode::OverFlow)?
+        / BPS_DENOMINATOR;
+
+    u64::try_from(scaled).or(Err(ErrorCode::OverFlow.into()))
+}
+
+// Share of `principal` withheld when leaving before `lock_period` ends, nothing once the lock is over.
+pub fn early_unstake_penalty(principal: u64, penalty_bps: u16, time_passed: i64, lock_period: i64) -> Result<u64> {
+    if time_passed >= lock_period {
+        return Ok(0);
+    }
+
+    apply_multiplier(principal, penalty_bps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MAX_ANNUAL_PERCENTAGE_RATE;
+    use proptest::prelude::*;
+
+    const DENOMINATOR: u128 = BPS_DENOMINATOR * SECONDS_PER_YEAR;
+
+    #[test]
+    fn full_year_at_100_percent_returns_principal() {
+        let one_year = SECONDS_PER_YEAR as i64;
+        assert_eq!(apr_yield(1_000_000_000, 10_000, one_year).unwrap(), 1_000_000_000);
+    }
+
+    #[test]
+    fn half_year_at_10_percent() {
+        let half_year = (SECONDS_PER_YEAR / 2) as i64;
+        assert_eq!(apr_yield(1_000_000, 1_000, half_year).unwrap(), 50_000);
+    }
+
+    #[test]
+    fn zero_or_negative_elapsed_earns_nothing() {
+        assert_eq!(apr_yield(u64::MAX, MAX_ANNUAL_PERCENTAGE_RATE, 0).unwrap(), 0);
+        assert_eq!(apr_yield(u64::MAX, MAX_ANNUAL_PERCENTAGE_RATE, -1).unwrap(), 0);
+    }
+
+    #[test]
+    fn result_too_large_for_u64_is_an_error() {
+        let two_years = (2 * SECONDS_PER_YEAR) as i64;
+        assert!(apr_yield(u64::MAX, 10_000, two_years).is_err());
+    }
+
+    #[test]
+    fn multiplier_scales_in_bps() {
+        assert_eq!(apply_multiplier(1_000, 10_000).unwrap(), 1_000);
+        assert_eq!(apply_multiplier(1_000, 25_000).unwrap(), 2_500);
+        assert_eq!(apply_multiplier(999, 5_000).unwrap(), 499);
+    }
+
+    #[test]
+    fn multiplier_overflow_is_an_error() {
+        assert!(apply_multiplier(u64::MAX, 20_000).is_err());
+    }
+
+    #[test]
+    fn penalty_only_applies_inside_the_lock() {
+        assert_eq!(early_unstake_penalty(1_000, 2_500, 59, 60).unwrap(), 250);
+        assert_eq!(early_unstake_penalty(1_000, 2_500, 60, 60).unwrap(), 0);
+        assert_eq!(early_unstake_penalty(1_000, 10_000, 0, 60).unwrap(), 1_000);
+    }
+
+    proptest! {
+        // u64 * u16 * i64 always fits in u128, so the only failure mode is a result above u64::MAX
+        #[test]
+        fn never_overflows_within_a_year_at_capped_apr(
+            principal in any::<u64>(),
+            apr_bps in 0..=MAX_ANNUAL_PERCENTAGE_RATE,
+            elapsed in 0..=SECONDS_PER_YEAR as i64,
+        ) {
+            let yield_amt = apr_yield(principal, apr_bps, elapsed).unwrap();
+            prop_assert!(yield_amt <= principal);
+        }
+
+        #[test]
+        fn rounds_down_to_the_exact_floor(
+            principal in any::<u64>(),
+            apr_bps in 0..=MAX_ANNUAL_PERCENTAGE_RATE,
+            elapsed in 0..=SECONDS_PER_YEAR as i64,
+        ) {
+            let exact = u128::from(principal) * u128::from(apr_bps) * elapsed as u128;
+            let yield_amt = u128::from(apr_yield(principal, apr_bps, elapsed).unwrap());
+
+            prop_assert!(yield_amt * DENOMINATOR <= exact);
+            prop_assert!(exact < (yield_amt + 1) * DENOMINATOR);
+        }
+
+        #[test]
+        fn splitting_elapsed_drifts_at_most_one_unit(
+            principal in any::<u64>(),
+            apr_bps in 0..=MAX_ANNUAL_PERCENTAGE_RATE,
+            first in 0..=(SECONDS_PER_YEAR / 2) as i64,
+            second in 0..=(SECONDS_PER_YEAR / 2) as i64,
+        ) {
+            let whole = apr_yield(principal, apr_bps, first + second).unwrap();
+            let parts = apr_yield(principal, apr_bps, first).unwrap() + apr_yield(principal, apr_bps, second).unwrap();
+
+            prop_assert!(parts <= whole);
+            prop_assert!(whole - parts <= 1);
+        }
+
+        #[test]
+        fn monotonic_in_elapsed(
+            principal in any::<u64>(),
+            apr_bps in 0..=MAX_ANNUAL_PERCENTAGE_RATE,
+            elapsed in 0..SECONDS_PER_YEAR as i64,
+        ) {
+            prop_assert!(apr_yield(principal, apr_bps, elapsed).unwrap() <= apr_yield(principal, apr_bps, elapsed + 1).unwrap());
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, transfer_checked, Mint, MintTo, TokenAccount, TransferChecked};
+
+use crate::{error::ErrorCode, events::RewardPaid, reward_math::{apply_multiplier, apr_yield}, AssetKind, RewardAccumulator, RewardMode, StakeAccount, StateConfig, UserAccount, NFT_YIELD_PRINCIPAL};
+
+// Bookkeeping side of every payout: debits the emission caps and credits the same amount
+// as points, so `UserAccount.points` always matches what was minted or transferred.
+pub fn settle_reward(config: &mut StateConfig, user_account: &mut UserAccount, amount: u64, epoch: u64) -> Result<()> {
+    config.record_emission(amount, epoch)?;
+    user_account.points = user_account.points.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+
+    Ok(())
+}
+
+// Reward side of every unstake: takes `amount` of the position out of its pool and returns what that
+// part earned (its pool share plus the locked-staker yield) and the reward debt left on the rest.
+// While paused the reward is forfeited since the reward math is not trusted during an incident.
+// NFTs are staked one at a time, so their yield runs on `NFT_YIELD_PRINCIPAL` per NFT instead of the count.
+pub fn settle_unstake(
+    accumulator: &mut RewardAccumulator,
+    reward_rate: u64,
+    stake_account: &StakeAccount,
+    amount: u64,
+    annual_percentage_rate: u16,
+    paused: bool,
+    now: i64,
+) -> Result<(u64, u128)> {
+    accumulator.update(reward_rate, now)?;
+    let (pending_reward, reward_debt) = accumulator.split_pending(stake_account.staked_amt, stake_account.reward_debt, amount)?;
+    accumulator.withdraw(amount)?;
+
+    if paused {
+        return Ok((0, reward_debt));
+    }
+
+    let mut reward_amount = pending_reward;
+
+    if stake_account.locked_stackers {
+        let time_passed = now.checked_sub(stake_account.staked_at).ok_or(ErrorCode::UnderFlow)?;
+        let principal = match stake_account.asset_kind {
+            AssetKind::Nft => amount.checked_mul(NFT_YIELD_PRINCIPAL).ok_or(ErrorCode::OverFlow)?,
+            AssetKind::Sol | AssetKind::Spl => amount,
+        };
+        let base_yield = apr_yield(principal, annual_percentage_rate, time_passed)?;
+        let yield_amt = apply_multiplier(base_yield, stake_account.lock_multiplier_bps)?;
+        reward_amount = reward_amount.checked_add(yield_amt).ok_or(ErrorCode::OverFlow)?;
+    }
+
+    Ok((reward_amount, reward_debt))
+}
+
+// The accounts every rewarding instruction already carries, borrowed from its `Accounts` struct.
+pub struct RewardPayout<'a, 'info> {
+    pub owner: Pubkey,
+    pub stake_account: Pubkey,
+    pub config: &'a mut Account<'info, StateConfig>,
+    pub user_account: &'a mut Account<'info, UserAccount>,
+    pub reward_mint: &'a InterfaceAccount<'info, Mint>,
+    pub user_reward_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    pub reward_vault: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'a, 'info> RewardPayout<'a, 'info> {
+    // Never pays less than `amount`: an exhausted cap or reward vault fails the instruction, unstakes
+    // included, and `emergency_unstake_*` remains the principal-only exit that needs no reward.
+    pub fn pay(self, amount: u64) -> Result<()> {
+        settle_reward(self.config, self.user_account, amount, Clock::get()?.epoch)?;
+
+        let seeds = &[
+            &b"config"[..],
+            &[self.config.bump]
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        match self.config.reward_mode {
+            RewardMode::Mint => {
+                let cpi_accounts = MintTo {
+                    mint: self.reward_mint.to_account_info(),
+                    to: self.user_reward_ata.to_account_info(),
+                    authority: self.config.to_account_info()
+                };
+
+                let ctx = CpiContext::new_with_signer(self.token_program, cpi_accounts, signer_seeds);
+
+                mint_to(ctx, amount)?;
+            }
+            RewardMode::Vault => {
+                let reward_vault = self.reward_vault.ok_or(ErrorCode::MissingRewardVault)?;
+
+                require!(reward_vault.amount >= amount, ErrorCode::InsufficientRewardFunds);
+
+                let cpi_accounts = TransferChecked {
+                    from: reward_vault.to_account_info(),
+                    mint: self.reward_mint.to_account_info(),
+                    to: self.user_reward_ata.to_account_info(),
+                    authority: self.config.to_account_info()
+                };
+
+                let ctx = CpiContext::new_with_signer(self.token_program, cpi_accounts, signer_seeds);
+
+                transfer_checked(ctx, amount, self.reward_mint.decimals)?;
+            }
+        }
+
+        emit!(RewardPaid {
+            owner: self.owner,
+            stake_account: self.stake_account,
+            reward_mint: self.reward_mint.key(),
+            amount,
+            paid_at: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LockTier;
+    use proptest::prelude::*;
+
+    fn config(max_total_emission: u64, epoch_emission_budget: u64) -> StateConfig {
+        StateConfig {
+            admin: Pubkey::default(),
+            pending_admin: None,
+            sol_reward_rate: 0,
+            min_freeze_period: 0,
+            annaul_percentage_rate: 0,
+            early_unstake_penalty_bps: 0,
+            sol_accumulator: RewardAccumulator::new(0),
+            reward_mode: RewardMode::Mint,
+            reward_mint: Pubkey::default(),
+            max_total_emission,
+            total_emitted: 0,
+            epoch_emission_budget,
+            current_epoch: 0,
+            epoch_emitted: 0,
+            validator_vote: None,
+            paused: 0,
+            rewards_bump: 0,
+            bump: 0,
+        }
+    }
+
+    fn user() -> UserAccount {
+        UserAccount {
+            points: 0,
+            nft_staked_amount: 0,
+            spl_staked_amount: 0,
+            sol_staked_amount: 0,
+            bump: 0,
+        }
+    }
+
+    fn stake_account(staked_amt: u64, locked_stackers: bool) -> StakeAccount {
+        position(AssetKind::Spl, staked_amt, locked_stackers)
+    }
+
+    fn position(asset_kind: AssetKind, staked_amt: u64, locked_stackers: bool) -> StakeAccount {
+        let tier = LockTier { duration: 0, multiplier_bps: 10_000 };
+        StakeAccount::open(Pubkey::default(), asset_kind, Pubkey::default(), staked_amt, 0, tier, locked_stackers, 0, 0, 0)
+    }
+
+    fn accumulator(total_staked: u64) -> RewardAccumulator {
+        let mut accumulator = RewardAccumulator::new(0);
+        accumulator.deposit(total_staked).unwrap();
+        accumulator
+    }
+
+    #[test]
+    fn unstake_pays_the_pool_share_plus_the_locked_yield() {
+        let staked = 1_000_000_000_000;
+
+        let (flexible, _) = settle_unstake(&mut accumulator(staked), 10, &stake_account(staked, false), staked, 10_000, false, 100).unwrap();
+        let (locked, _) = settle_unstake(&mut accumulator(staked), 10, &stake_account(staked, true), staked, 10_000, false, 100).unwrap();
+
+        let yield_amt = apr_yield(staked, 10_000, 100).unwrap();
+        assert!(yield_amt > 0);
+        assert_eq!(flexible, 1_000);
+        assert_eq!(locked, flexible + yield_amt);
+    }
+
+    #[test]
+    fn locked_nft_earns_yield_on_the_nft_principal() {
+        let year = 365 * 24 * 60 * 60;
+
+        let (flexible, _) = settle_unstake(&mut accumulator(1), 0, &position(AssetKind::Nft, 1, false), 1, 1_000, false, year).unwrap();
+        let (locked, _) = settle_unstake(&mut accumulator(1), 0, &position(AssetKind::Nft, 1, true), 1, 1_000, false, year).unwrap();
+
+        assert_eq!(flexible, 0);
+        assert_eq!(locked, NFT_YIELD_PRINCIPAL / 10);
+    }
+
+    #[test]
+    fn paused_unstake_forfeits_the_reward_but_leaves_the_pool() {
+        let mut accumulator = accumulator(1_000);
+
+        let (reward, reward_debt) = settle_unstake(&mut accumulator, 10, &stake_account(1_000, true), 400, 1_000, true, 100).unwrap();
+
+        assert_eq!(reward, 0);
+        assert_eq!(accumulator.total_staked, 600);
+        assert_eq!(accumulator.pending_reward(600, reward_debt).unwrap(), 600);
+    }
+
+    #[test]
+    fn rejected_payout_credits_no_points() {
+        let mut config = config(100, u64::MAX);
+        let mut user = user();
+
+        settle_reward(&mut config, &mut user, 60, 0).unwrap();
+        assert!(settle_reward(&mut config, &mut user, 60, 0).is_err());
+
+        assert_eq!(user.points, 60);
+        assert_eq!(config.total_emitted, 60);
+    }
+
+    proptest! {
+        #[test]
+        fn points_always_equal_tokens_paid(
+            payouts in prop::collection::vec((0..1_000_000_000u64, 0..4u64), 0..64),
+        ) {
+            let mut config = config(u64::MAX, u64::MAX);
+            let mut user = user();
+            let mut paid = 0u64;
+
+            for (amount, epoch) in payouts {
+                settle_reward(&mut config, &mut user, amount, epoch).unwrap();
+                paid += amount;
+
+                prop_assert_eq!(user.points, paid);
+                prop_assert_eq!(config.total_emitted, paid);
+            }
+        }
+
+        #[test]
+        fn capped_payouts_keep_points_and_emission_in_sync(
+            max_total_emission in 0..10_000u64,
+            payouts in prop::collection::vec(0..1_000u64, 0..64),
+        ) {
+            let mut config = config(max_total_emission, u64::MAX);
+            let mut user = user();
+
+            for amount in payouts {
+                let _ = settle_reward(&mut config, &mut user, amount, 0);
+
+                prop_assert_eq!(user.points, config.total_emitted);
+                prop_assert!(config.total_emitted <= max_total_emission);
+            }
+        }
+    }
+}
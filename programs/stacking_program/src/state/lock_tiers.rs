@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, MAX_LOCK_MULTIPLIER_BPS, MAX_LOCK_TIERS};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct LockTier {
+    pub duration: i64, // seconds
+    pub multiplier_bps: u16, // applied to the APR yield, 10_000 = 1x
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LockTiers {
+    #[max_len(MAX_LOCK_TIERS)]
+    pub tiers: Vec<LockTier>, // sorted by ascending duration
+    pub bump: u8,
+}
+
+impl LockTiers {
+    pub fn validate(tiers: &[LockTier]) -> Result<()> {
+        require!(!tiers.is_empty() && tiers.len() <= MAX_LOCK_TIERS, ErrorCode::InvalidLockTiers);
+        require!(tiers[0].duration >= 0, ErrorCode::InvalidLockTiers);
+        require!(tiers.windows(2).all(|w| w[0].duration < w[1].duration), ErrorCode::InvalidLockTiers);
+        require!(tiers.iter().all(|t| t.multiplier_bps <= MAX_LOCK_MULTIPLIER_BPS), ErrorCode::InvalidLockTiers);
+
+        Ok(())
+    }
+
+    // longest tier that the requested lock period fully covers
+    pub fn snap(&self, lock_period: i64) -> Result<LockTier> {
+        let tier = self.tiers.iter().rev().find(|t| t.duration <= lock_period).ok_or(ErrorCode::TooLessStakePeriod)?;
+
+        Ok(*tier)
+    }
+
+    // the requested period is snapped down to the longest tier it covers, which must still meet the minimum freeze period
+    pub fn lock_tier(&self, lock_period: i64, min_freeze_period: i64) -> Result<LockTier> {
+        let tier = self.snap(lock_period)?;
+        require!(tier.duration >= min_freeze_period, ErrorCode::TooLessStakePeriod);
+
+        Ok(tier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn tiers() -> LockTiers {
+        LockTiers {
+            tiers: vec![
+                LockTier { duration: 30 * DAY, multiplier_bps: 10_000 },
+                LockTier { duration: 90 * DAY, multiplier_bps: 12_500 },
+                LockTier { duration: 180 * DAY, multiplier_bps: 15_000 },
+                LockTier { duration: 365 * DAY, multiplier_bps: 20_000 },
+            ],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn snaps_down_to_the_longest_covered_tier() {
+        let tiers = tiers();
+
+        assert_eq!(tiers.snap(30 * DAY).unwrap().duration, 30 * DAY);
+        assert_eq!(tiers.snap(179 * DAY).unwrap().duration, 90 * DAY);
+        assert_eq!(tiers.snap(1_000 * DAY).unwrap().duration, 365 * DAY);
+    }
+
+    #[test]
+    fn below_the_shortest_tier_is_rejected() {
+        assert!(tiers().snap(29 * DAY).is_err());
+    }
+
+    #[test]
+    fn lock_tier_enforces_the_minimum_freeze_period() {
+        let tiers = tiers();
+
+        assert_eq!(tiers.lock_tier(100 * DAY, 90 * DAY).unwrap().duration, 90 * DAY);
+        assert!(tiers.lock_tier(89 * DAY, 60 * DAY).is_err());
+    }
+
+    #[test]
+    fn validate_requires_ascending_durations_and_capped_multipliers() {
+        assert!(LockTiers::validate(&tiers().tiers).is_ok());
+        assert!(LockTiers::validate(&[]).is_err());
+
+        let mut unsorted = tiers().tiers;
+        unsorted.swap(0, 1);
+        assert!(LockTiers::validate(&unsorted).is_err());
+
+        let mut too_high = tiers().tiers;
+        too_high[0].multiplier_bps = MAX_LOCK_MULTIPLIER_BPS + 1;
+        assert!(LockTiers::validate(&too_high).is_err());
+
+        let too_many = vec![LockTier { duration: 0, multiplier_bps: 10_000 }; MAX_LOCK_TIERS + 1];
+        assert!(LockTiers::validate(&too_many).is_err());
+    }
+}
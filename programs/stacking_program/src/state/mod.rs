@@ -1,7 +1,17 @@
 pub mod state_config;
 pub mod user_account;
 pub mod stake_account;
+pub mod spl_pool;
+pub mod nft_collection_pool;
+pub mod reward_accumulator;
+pub mod lock_tiers;
+pub mod treasury;
 
 pub use state_config::*;
 pub use user_account::*;
-pub use stake_account::*;
\ No newline at end of file
+pub use stake_account::*;
+pub use spl_pool::*;
+pub use nft_collection_pool::*;
+pub use reward_accumulator::*;
+pub use lock_tiers::*;
+pub use treasury::*;
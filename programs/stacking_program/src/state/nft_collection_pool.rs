@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+use crate::RewardAccumulator;
+
+#[account]
+#[derive(InitSpace)]
+pub struct NftCollectionPool {
+    pub collection_mint: Pubkey,
+    pub allowed: bool,
+    pub reward_rate: u64, // tokens per second shared by all staked NFTs of this collection
+    pub accumulator: RewardAccumulator, // total_staked is the live staked count
+    pub bump: u8,
+}
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, ACC_REWARD_PRECISION};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RewardAccumulator {
+    pub acc_reward_per_share: u128, // scaled by ACC_REWARD_PRECISION
+    pub last_update_ts: i64,
+    pub total_staked: u64,
+}
+
+impl RewardAccumulator {
+    pub fn new(now: i64) -> Self {
+        Self {
+            acc_reward_per_share: 0,
+            last_update_ts: now,
+            total_staked: 0,
+        }
+    }
+
+    // spreads `reward_rate` tokens per second since the last update over everything currently staked,
+    // must be called before `total_staked` or the rate changes so past time is priced at the old values
+    pub fn update(&mut self, reward_rate: u64, now: i64) -> Result<()> {
+        if now <= self.last_update_ts {
+            return Ok(());
+        }
+
+        if self.total_staked > 0 {
+            let elapsed = u128::try_from(now - self.last_update_ts).or(Err(ErrorCode::OverFlow))?;
+            let reward = u128::from(reward_rate).checked_mul(elapsed).ok_or(ErrorCode::OverFlow)?;
+            let reward_per_share = reward
+                .checked_mul(ACC_REWARD_PRECISION).ok_or(ErrorCode::OverFlow)?
+                .checked_div(u128::from(self.total_staked)).ok_or(ErrorCode::OverFlow)?;
+
+            self.acc_reward_per_share = self.acc_reward_per_share.checked_add(reward_per_share).ok_or(ErrorCode::OverFlow)?;
+        }
+
+        self.last_update_ts = now;
+
+        Ok(())
+    }
+
+    pub fn reward_debt(&self, staked_amt: u64) -> Result<u128> {
+        let accrued = u128::from(staked_amt).checked_mul(self.acc_reward_per_share).ok_or(ErrorCode::OverFlow)?;
+        Ok(accrued / ACC_REWARD_PRECISION)
+    }
+
+    pub fn pending_reward(&self, staked_amt: u64, reward_debt: u128) -> Result<u64> {
+        let pending = self.reward_debt(staked_amt)?.checked_sub(reward_debt).ok_or(ErrorCode::UnderFlow)?;
+        let pending = u64::try_from(pending).or(Err(ErrorCode::OverFlow))?;
+        Ok(pending)
+    }
+
+    // Splits a position's pending reward pro-rata: returns what `amount` of it has earned and the reward
+    // debt that keeps the remaining `staked_amt - amount` earning its share. Rounding dust goes to the
+    // remaining side, and the two parts always add up to exactly the position's pending reward.
+    pub fn split_pending(&self, staked_amt: u64, reward_debt: u128, amount: u64) -> Result<(u64, u128)> {
+        let remaining = staked_amt.checked_sub(amount).ok_or(ErrorCode::UnderFlow)?;
+        let total = self.pending_reward(staked_amt, reward_debt)?;
+
+        let mut remaining_debt = reward_debt
+            .checked_mul(u128::from(remaining)).ok_or(ErrorCode::OverFlow)?
+            .checked_div(u128::from(staked_amt)).ok_or(ErrorCode::OverFlow)?;
+        let left = self.pending_reward(remaining, remaining_debt)?;
+
+        // flooring both terms can leave the remaining side a unit above the whole position
+        if left > total {
+            remaining_debt = remaining_debt.checked_add(u128::from(left - total)).ok_or(ErrorCode::OverFlow)?;
+        }
+
+        Ok((total - left.min(total), remaining_debt))
+    }
+
+    // settles the pool before a new stake of `amount` joins so it does not earn past rewards,
+    // returns the reward debt the new position starts with
+    pub fn join(&mut self, reward_rate: u64, amount: u64, now: i64) -> Result<u128> {
+        self.update(reward_rate, now)?;
+        let reward_debt = self.reward_debt(amount)?;
+        self.deposit(amount)?;
+
+        Ok(reward_debt)
+    }
+
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_staked = self.total_staked.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
+        self.total_staked = self.total_staked.checked_sub(amount).ok_or(ErrorCode::UnderFlow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn accumulator(acc_reward_per_share: u128) -> RewardAccumulator {
+        RewardAccumulator {
+            acc_reward_per_share,
+            last_update_ts: 0,
+            total_staked: 0,
+        }
+    }
+
+    #[test]
+    fn update_spreads_the_rate_over_total_staked() {
+        let mut acc = RewardAccumulator::new(0);
+        acc.deposit(1_000).unwrap();
+
+        acc.update(10, 100).unwrap();
+
+        // 10 tokens/s for 100s over 1_000 staked is one token per staked unit
+        assert_eq!(acc.acc_reward_per_share, ACC_REWARD_PRECISION);
+        assert_eq!(acc.last_update_ts, 100);
+        assert_eq!(acc.pending_reward(250, 0).unwrap(), 250);
+    }
+
+    #[test]
+    fn empty_pool_accrues_nothing_but_moves_the_checkpoint() {
+        let mut acc = RewardAccumulator::new(0);
+
+        acc.update(10, 100).unwrap();
+
+        assert_eq!(acc.acc_reward_per_share, 0);
+        assert_eq!(acc.last_update_ts, 100);
+    }
+
+    #[test]
+    fn update_ignores_time_going_backwards() {
+        let mut acc = RewardAccumulator::new(100);
+        acc.deposit(1).unwrap();
+
+        acc.update(10, 50).unwrap();
+
+        assert_eq!(acc.acc_reward_per_share, 0);
+        assert_eq!(acc.last_update_ts, 100);
+    }
+
+    #[test]
+    fn reward_debt_excludes_rewards_earned_before_staking() {
+        let acc = accumulator(3 * ACC_REWARD_PRECISION);
+        let debt = acc.reward_debt(100).unwrap();
+
+        assert_eq!(debt, 300);
+        assert_eq!(acc.pending_reward(100, debt).unwrap(), 0);
+    }
+
+    #[test]
+    fn joining_stake_earns_nothing_from_before_it_joined() {
+        let mut acc = RewardAccumulator::new(0);
+        acc.deposit(1_000).unwrap();
+
+        let debt = acc.join(10, 500, 100).unwrap();
+
+        assert_eq!(acc.total_staked, 1_500);
+        assert_eq!(acc.pending_reward(500, debt).unwrap(), 0);
+
+        acc.update(10, 250).unwrap();
+        assert_eq!(acc.pending_reward(500, debt).unwrap(), 500);
+    }
+
+    #[test]
+    fn rate_change_does_not_reprice_past_time() {
+        let mut acc = RewardAccumulator::new(0);
+        acc.deposit(100).unwrap();
+
+        acc.update(1, 100).unwrap();
+        acc.update(5, 200).unwrap();
+
+        assert_eq!(acc.pending_reward(100, 0).unwrap(), 100 + 500);
+    }
+
+    #[test]
+    fn withdraw_more_than_staked_is_an_error() {
+        let mut acc = RewardAccumulator::new(0);
+        acc.deposit(10).unwrap();
+
+        assert!(acc.withdraw(11).is_err());
+        acc.withdraw(10).unwrap();
+        assert_eq!(acc.total_staked, 0);
+    }
+
+    #[test]
+    fn full_split_matches_pending_reward() {
+        let acc = accumulator(3 * ACC_REWARD_PRECISION / 2);
+
+        let (pending, remaining_debt) = acc.split_pending(1_000, 500, 1_000).unwrap();
+
+        assert_eq!(pending, acc.pending_reward(1_000, 500).unwrap());
+        assert_eq!(remaining_debt, 0);
+    }
+
+    proptest! {
+        #[test]
+        fn split_pays_exactly_what_the_position_earned(
+            acc_reward_per_share in 0..1_000 * ACC_REWARD_PRECISION,
+            staked_amt in 1..1_000_000_000_000u64,
+            debt_share in 0..=100u128,
+            withdrawn_share in 0..=100u64,
+        ) {
+            let acc = accumulator(acc_reward_per_share);
+            let reward_debt = acc.reward_debt(staked_amt).unwrap() * debt_share / 100;
+            let amount = (u128::from(staked_amt) * u128::from(withdrawn_share) / 100) as u64;
+
+            let total = acc.pending_reward(staked_amt, reward_debt).unwrap();
+            let (paid, remaining_debt) = acc.split_pending(staked_amt, reward_debt, amount).unwrap();
+            let left = acc.pending_reward(staked_amt - amount, remaining_debt).unwrap();
+
+            prop_assert_eq!(paid + left, total);
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+use crate::RewardAccumulator;
+
+#[account]
+#[derive(InitSpace)]
+pub struct SplPool {
+    pub mint: Pubkey,
+    pub reward_rate: u64, // tokens per second shared by all stakers of this mint
+    pub min_stake_amount: u64,
+    pub max_stake_amount: u64,
+    pub accumulator: RewardAccumulator,
+    pub enabled: bool,
+    pub bump: u8,
+}
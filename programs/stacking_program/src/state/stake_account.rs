@@ -1,15 +1,77 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::ErrorCode, LockTier};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AssetKind {
+    Sol,
+    Spl,
+    Nft,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct StakeAccount {
     pub owner: Pubkey,
     pub mint: Pubkey,
+    pub collection: Pubkey, // collection mint for NFT stakes, default otherwise
+    pub asset_kind: AssetKind,
     pub staked_amt: u64,
     pub staked_at: i64,
+    pub last_claimed_at: i64,
+    pub reward_debt: u128, // share of the pool accumulator already paid out or accrued before staking
     pub lock_period: i64,
     pub locked_stackers: bool,
+    pub lock_multiplier_bps: u16, // multiplier of the lock tier snapped to at stake time
+    pub auto_compound: bool, // rewards are restaked by `compound`, only for flexible reward mint positions
     pub bump: u8,
-    // pub vault_bump: u8,
+    pub vault_bump: u8, // SOL stakes only, bump of the `[b"vault", stake_account]` PDA holding the lamports
+    pub native_stake_bump: u8, // SOL stakes only, bump of the `[b"native_stake", stake_account]` PDA once delegated
+    pub stake_activation_epoch: Option<u64>, // set while the principal is delegated to the validator
+    pub stake_deactivation_epoch: Option<u64>,
+    pub merkle_tree: Pubkey, // compressed NFT stakes only, `mint` holds the asset id
+    pub leaf_index: u32,
+    pub escrowed: bool, // NFT stakes only, held in the stake account's vault ATA instead of frozen in the wallet
     pub seed: u64,
-}
\ No newline at end of file
+}
+
+impl StakeAccount {
+    // A new position locked for the snapped `tier`, `reward_debt` comes from `RewardAccumulator::join`.
+    // Fields only some asset kinds use (collection, vault bump, cNFT leaf, escrow) are left empty.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open(owner: Pubkey, asset_kind: AssetKind, mint: Pubkey, staked_amt: u64, reward_debt: u128, tier: LockTier, locked_stackers: bool, bump: u8, seed: u64, now: i64) -> Self {
+        Self {
+            owner,
+            mint,
+            collection: Pubkey::default(),
+            asset_kind,
+            staked_amt,
+            staked_at: now,
+            last_claimed_at: now,
+            reward_debt,
+            lock_period: tier.duration,
+            locked_stackers,
+            lock_multiplier_bps: tier.multiplier_bps,
+            auto_compound: false,
+            bump,
+            vault_bump: 0,
+            native_stake_bump: 0,
+            stake_activation_epoch: None,
+            stake_deactivation_epoch: None,
+            merkle_tree: Pubkey::default(),
+            leaf_index: 0,
+            escrowed: false,
+            seed,
+        }
+    }
+
+    // top-ups may keep or extend the lock but never shorten it
+    pub fn extend_lock(&mut self, tier: LockTier) -> Result<()> {
+        require!(tier.duration >= self.lock_period, ErrorCode::TooLessStakePeriod);
+
+        self.lock_period = tier.duration;
+        self.lock_multiplier_bps = tier.multiplier_bps;
+
+        Ok(())
+    }
+}
@@ -1,14 +1,138 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::ErrorCode, AssetKind, RewardAccumulator, MAX_ANNUAL_PERCENTAGE_RATE, MAX_EARLY_UNSTAKE_PENALTY_BPS, PAUSE_NFT, PAUSE_SOL, PAUSE_SPL};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RewardMode {
+    Mint,  // rewards are minted by the config PDA, unbounded supply
+    Vault, // rewards are paid out of a config owned vault funded by the admin
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct StateConfig {
-    pub points_per_nft_stake: u8,
-    pub points_per_sol_stake: u8,
-    pub points_per_spl_stake: u8,
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub sol_reward_rate: u64, // tokens per second shared by all SOL stakers
     pub min_freeze_period: i64,
     pub annaul_percentage_rate: u16,
-    pub rewards_bump: u8,
+    pub early_unstake_penalty_bps: u16, // share of principal sent to the treasury on an early emergency unstake
+    pub sol_accumulator: RewardAccumulator,
+    pub reward_mode: RewardMode,
+    pub reward_mint: Pubkey,
+    pub max_total_emission: u64,
+    pub total_emitted: u64,
+    pub epoch_emission_budget: u64,
+    pub current_epoch: u64,
+    pub epoch_emitted: u64, // reset whenever a reward is paid in a new epoch
+    pub validator_vote: Option<Pubkey>, // vote account SOL stakes can be delegated to, None disables delegation
+    pub paused: u8, // bitmask of PAUSE_SOL | PAUSE_SPL | PAUSE_NFT
+    pub rewards_bump: u8, // only set in RewardMode::Mint
     pub bump: u8,
 }
 
+impl StateConfig {
+    // bounds shared by `initialize_config` and `update_config`, so a config can always be updated to its own values
+    pub fn validate(&self) -> Result<()> {
+        require!(self.min_freeze_period >= 0, ErrorCode::InvalidFreezePeriod);
+        require!(self.annaul_percentage_rate <= MAX_ANNUAL_PERCENTAGE_RATE, ErrorCode::AprTooHigh);
+        require!(self.early_unstake_penalty_bps <= MAX_EARLY_UNSTAKE_PENALTY_BPS, ErrorCode::PenaltyTooHigh);
+
+        Ok(())
+    }
+
+    pub fn is_paused(&self, asset_kind: AssetKind) -> bool {
+        let flag = match asset_kind {
+            AssetKind::Sol => PAUSE_SOL,
+            AssetKind::Spl => PAUSE_SPL,
+            AssetKind::Nft => PAUSE_NFT,
+        };
+
+        self.paused & flag != 0
+    }
+
+    pub fn record_emission(&mut self, amount: u64, epoch: u64) -> Result<()> {
+        if epoch != self.current_epoch {
+            self.current_epoch = epoch;
+            self.epoch_emitted = 0;
+        }
+
+        let total_emitted = self.total_emitted.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+        require!(total_emitted <= self.max_total_emission, ErrorCode::EmissionCapExceeded);
+
+        let epoch_emitted = self.epoch_emitted.checked_add(amount).ok_or(ErrorCode::OverFlow)?;
+        require!(epoch_emitted <= self.epoch_emission_budget, ErrorCode::EpochBudgetExceeded);
+
+        self.total_emitted = total_emitted;
+        self.epoch_emitted = epoch_emitted;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_total_emission: u64, epoch_emission_budget: u64) -> StateConfig {
+        let data = [0; 8 + StateConfig::INIT_SPACE];
+        let mut config = StateConfig::try_deserialize_unchecked(&mut &data[..]).unwrap();
+        config.max_total_emission = max_total_emission;
+        config.epoch_emission_budget = epoch_emission_budget;
+        config
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_parameters() {
+        let mut config = config(0, 0);
+        config.validate().unwrap();
+
+        config.min_freeze_period = -1;
+        assert!(config.validate().is_err());
+        config.min_freeze_period = 0;
+
+        config.annaul_percentage_rate = MAX_ANNUAL_PERCENTAGE_RATE + 1;
+        assert!(config.validate().is_err());
+        config.annaul_percentage_rate = MAX_ANNUAL_PERCENTAGE_RATE;
+
+        config.early_unstake_penalty_bps = MAX_EARLY_UNSTAKE_PENALTY_BPS + 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn records_emission_within_both_caps() {
+        let mut config = config(1_000, 100);
+
+        config.record_emission(60, 1).unwrap();
+        config.record_emission(40, 1).unwrap();
+
+        assert_eq!(config.total_emitted, 100);
+        assert_eq!(config.epoch_emitted, 100);
+    }
+
+    #[test]
+    fn epoch_budget_is_enforced_and_resets_each_epoch() {
+        let mut config = config(1_000, 100);
+
+        config.record_emission(100, 1).unwrap();
+        assert!(config.record_emission(1, 1).is_err());
+
+        config.record_emission(100, 2).unwrap();
+        assert_eq!(config.current_epoch, 2);
+        assert_eq!(config.epoch_emitted, 100);
+        assert_eq!(config.total_emitted, 200);
+    }
+
+    #[test]
+    fn total_cap_is_enforced_across_epochs() {
+        let mut config = config(150, 100);
+
+        config.record_emission(100, 1).unwrap();
+        assert!(config.record_emission(60, 2).is_err());
+
+        // a rejected emission leaves the counters untouched
+        assert_eq!(config.total_emitted, 100);
+        assert_eq!(config.epoch_emitted, 0);
+        config.record_emission(50, 2).unwrap();
+    }
+}
@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+
+// Program owned PDA collecting early unstake penalties: lamports for SOL, its ATAs for SPL mints.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub bump: u8,
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+        state::Account,
+    },
+    token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+};
+
+// transfer fees Token-2022 withheld in a token account, zero for classic SPL Token accounts
+pub fn withheld_fees(data: &[u8]) -> Result<u64> {
+    let account = StateWithExtensions::<Account>::unpack(data)?;
+
+    Ok(account
+        .get_extension::<TransferFeeAmount>()
+        .map(|fee| u64::from(fee.withheld_amount))
+        .unwrap_or(0))
+}
+
+// Fees withheld in a vault block `close_account` until they are harvested. Harvesting is
+// permissionless, so they are sent back to the mint right before the vault is closed.
+pub fn harvest_withheld_fees<'info>(token_program: AccountInfo<'info>, mint: AccountInfo<'info>, vault: AccountInfo<'info>) -> Result<()> {
+    if *vault.owner != spl_token_2022::ID || withheld_fees(&vault.try_borrow_data()?)? == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = HarvestWithheldTokensToMint {
+        token_program_id: token_program.clone(),
+        mint,
+    };
+
+    harvest_withheld_tokens_to_mint(CpiContext::new(token_program, cpi_accounts), vec![vault])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::{BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut},
+        state::AccountState,
+    };
+
+    fn vault(withheld_amount: u64) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::TransferFeeAmount]).unwrap();
+        let mut data = vec![0; len];
+
+        let mut account = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        account.base = Account {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            state: AccountState::Initialized,
+            ..Account::default()
+        };
+        account.pack_base();
+        account.init_account_type().unwrap();
+        account.init_extension::<TransferFeeAmount>(false).unwrap().withheld_amount = withheld_amount.into();
+
+        data
+    }
+
+    #[test]
+    fn reads_withheld_fees() {
+        assert_eq!(withheld_fees(&vault(42)).unwrap(), 42);
+        assert_eq!(withheld_fees(&vault(0)).unwrap(), 0);
+    }
+
+    #[test]
+    fn accounts_without_the_extension_withhold_nothing() {
+        let mut data = vec![0; Account::LEN];
+        Account::pack(
+            Account {
+                state: AccountState::Initialized,
+                ..Account::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+
+        assert_eq!(withheld_fees(&data).unwrap(), 0);
+    }
+}
@@ -2,16 +2,17 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { StackingProgram } from "../target/types/stacking_program";
 import wallet from "../Admin-wallet.json";
-import { Commitment, Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
-import { createMint, getAssociatedTokenAddress, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Commitment, Keypair, LAMPORTS_PER_SOL, PublicKey, Transaction } from "@solana/web3.js";
+import { createInitializeMintInstruction, createInitializeTransferFeeConfigInstruction, createMint, ExtensionType, getAssociatedTokenAddress, getAssociatedTokenAddressSync, getMintLen, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { assert, use } from "chai";
 import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
 import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
 import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
-import { createSignerFromKeypair, generateSigner, keypairIdentity, KeypairSigner, percentAmount } from "@metaplex-foundation/umi";
-import { createNft, findMasterEditionPda, findMetadataPda, mplTokenMetadata, verifySizedCollectionItem } from "@metaplex-foundation/mpl-token-metadata";
+import { createSignerFromKeypair, generateSigner, keypairIdentity, KeypairSigner, percentAmount, publicKey } from "@metaplex-foundation/umi";
+import { createMetadataAccountV3, createNft, findMasterEditionPda, findMetadataPda, mplTokenMetadata, verifySizedCollectionItem } from "@metaplex-foundation/mpl-token-metadata";
 import { BN } from "bn.js";
 import { randomBytes } from "crypto";
+import { batchNftAccounts, createBatchLookupTable, sendV0 } from "../app/lookup_table";
 
 describe("stacking_program", () => {
 
@@ -173,17 +174,19 @@ describe("stacking_program", () => {
     
     const tx = await program.methods
     .initializeConfig(
-      100, // 100 token per second
-      10,  // 10 token per second
-      1,  // 1 token per second
+      new anchor.BN(10), // 10 token per second
       new anchor.BN(60), // 2 minutes min_lock periode
       100,
+      { mint: {} },
     )
     .accountsStrict({
       admin: admin.publicKey,
       config: config,
       rewardMint: reward_mint,
+      vaultRewardMint: null,
+      rewardVault: null,
       tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId
     })
     .signers([admin])
@@ -192,6 +195,108 @@ describe("stacking_program", () => {
     console.log("Your transaction signature", tx);
   });
 
+  it("Update config", async () => {
+
+    const tx = await program.methods
+    .updateConfig(
+      null,
+      null,
+      200,
+      null,
+      null,
+      1_000,
+    )
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+    })
+    .signers([admin])
+    .rpc();
+
+    console.log("Your transaction signature", tx);
+
+    const config_pda = await program.account.stateConfig.fetch(config);
+    assert(config_pda.annaulPercentageRate === 200, "APR not updated");
+    assert(config_pda.earlyUnstakePenaltyBps === 1_000, "Penalty not updated");
+  });
+
+  it("Transfer admin and back", async () => {
+
+    await program.methods
+    .proposeAdmin(user.publicKey)
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+    })
+    .signers([admin])
+    .rpc();
+
+    await program.methods
+    .acceptAdmin()
+    .accountsStrict({
+      newAdmin: user.publicKey,
+      config: config,
+    })
+    .signers([user])
+    .rpc();
+
+    let config_pda = await program.account.stateConfig.fetch(config);
+    assert(config_pda.admin.equals(user.publicKey), "Admin not transferred");
+
+    await program.methods
+    .proposeAdmin(admin.publicKey)
+    .accountsStrict({
+      admin: user.publicKey,
+      config: config,
+    })
+    .signers([user])
+    .rpc();
+
+    const tx = await program.methods
+    .acceptAdmin()
+    .accountsStrict({
+      newAdmin: admin.publicKey,
+      config: config,
+    })
+    .signers([admin])
+    .rpc();
+
+    console.log("Your transaction signature", tx);
+
+    config_pda = await program.account.stateConfig.fetch(config);
+    assert(config_pda.admin.equals(admin.publicKey), "Admin not transferred back");
+    assert(config_pda.pendingAdmin === null, "Pending admin not cleared");
+  });
+
+  it("Pause and unpause staking", async () => {
+
+    await program.methods
+    .setPaused(0b111)
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+    })
+    .signers([admin])
+    .rpc();
+
+    let config_pda = await program.account.stateConfig.fetch(config);
+    assert(config_pda.paused === 0b111, "Staking not paused");
+
+    const tx = await program.methods
+    .setPaused(0)
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+    })
+    .signers([admin])
+    .rpc();
+
+    console.log("Your transaction signature", tx);
+
+    config_pda = await program.account.stateConfig.fetch(config);
+    assert(config_pda.paused === 0, "Staking not unpaused");
+  });
+
   let user_account: PublicKey;
   let user_reward_ata: PublicKey;
   let stake_account: PublicKey;
@@ -222,6 +327,120 @@ describe("stacking_program", () => {
   let masterEditon: PublicKey;
   let nft_mint_ata: PublicKey;
 
+  let treasury: PublicKey;
+  it("Initialize treasury", async () => {
+
+    treasury = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), config.toBuffer()],
+      program.programId
+    )[0];
+
+    const tx = await program.methods
+    .initializeTreasury()
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+      treasury: treasury,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([admin])
+    .rpc()
+
+    console.log("tx :", tx);
+  })
+
+  let lock_tiers: PublicKey;
+  it("create lock tiers", async () => {
+
+    lock_tiers = PublicKey.findProgramAddressSync(
+      [Buffer.from("lock_tiers"), config.toBuffer()],
+      program.programId
+    )[0];
+
+    const tx = await program.methods
+    .createLockTiers([
+      { duration: new anchor.BN(60), multiplierBps: 10_000 },
+      { duration: new anchor.BN(120), multiplierBps: 12_500 },
+      { duration: new anchor.BN(240), multiplierBps: 15_000 },
+    ])
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+      lockTiers: lock_tiers,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([admin])
+    .rpc()
+
+    console.log("tx :", tx);
+
+    const lock_tiers_pda = await program.account.lockTiers.fetch(lock_tiers);
+    assert(lock_tiers_pda.tiers.length == 3, "Lock tiers not stored");
+  })
+
+  it("set and clear validator", async () => {
+
+    // the local validator's own vote account
+    const vote_accounts = await connection.getVoteAccounts();
+    const vote_account = new PublicKey(vote_accounts.current[0].votePubkey);
+
+    let tx = await program.methods
+    .setValidator()
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+      voteAccount: vote_account,
+    })
+    .signers([admin])
+    .rpc()
+
+    console.log("tx :", tx);
+
+    let config_pda = await program.account.stateConfig.fetch(config);
+    assert(config_pda.validatorVote.equals(vote_account), "Validator not set");
+
+    tx = await program.methods
+    .setValidator()
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+      voteAccount: null,
+    })
+    .signers([admin])
+    .rpc()
+
+    console.log("tx :", tx);
+
+    config_pda = await program.account.stateConfig.fetch(config);
+    assert(config_pda.validatorVote === null, "Validator not cleared");
+  })
+
+  let nft_pool: PublicKey;
+  it("create nft pool", async () => {
+
+    nft_pool = PublicKey.findProgramAddressSync(
+      [Buffer.from("nft_pool"), config.toBuffer(), collection.toBuffer()],
+      program.programId
+    )[0];
+
+    const tx = await program.methods
+    .createNftPool(new anchor.BN(100))
+    .accountsStrict({
+      admin: admin.publicKey,
+      collectionMint: collection,
+      config: config,
+      nftPool: nft_pool,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([admin])
+    .rpc()
+
+    console.log("tx :", tx);
+
+    const nft_pool_pda = await program.account.nftCollectionPool.fetch(nft_pool);
+    assert(nft_pool_pda.allowed, "Collection not allowed");
+  })
+
   let seed: any;
   it("stake NFT" ,async ()=> {
     seed = new BN(randomBytes(8));
@@ -274,12 +493,22 @@ describe("stacking_program", () => {
       mintAta: nft_mint_ata,
       rewardMint: reward_mint,
       userRewardAta: user_reward_ata,
+      rewardVault: null,
       metadata: metadata,
       masterEdition: masterEditon,
+      vaultAta: null, // the NFT has a master edition, so it is frozen in place
+      // legacy NFT, the programmable accounts are left out
+      tokenRecord: null,
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
       stakeAccount: stake_account,
       config: config,
+      lockTiers: lock_tiers,
+      nftPool: nft_pool,
       userAccount: user_account,
       tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
       metadataProgram: new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"),
       systemProgram: anchor.web3.SystemProgram.programId
     })
@@ -353,10 +582,18 @@ describe("stacking_program", () => {
       mintAta: nft_mint_ata,
       rewardMint: reward_mint,
       userRewardAta: user_reward_ata,
+      rewardVault: null,
       metadata: metadata,
       masterEdition: masterEditon,
+      vaultAta: null, // the NFT has a master edition, so it is frozen in place
+      // legacy NFT, the programmable accounts are left out
+      tokenRecord: null,
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
       stakeAccount: stake_account,
       config: config,
+      nftPool: nft_pool,
       userAccount: user_account,
       tokenProgram: TOKEN_PROGRAM_ID,
       metadataProgram: new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"),
@@ -372,6 +609,238 @@ describe("stacking_program", () => {
   })
 
 
+  it("another user can't unstake an escrowed NFT", async () => {
+    // plain SPL NFT with metadata but no edition, it can't be frozen so staking escrows it
+    const escrow_mint = await createMint(connection, user, user.publicKey, user.publicKey, 0);
+    const escrow_mint_ata = (await getOrCreateAssociatedTokenAccount(connection, user, escrow_mint, user.publicKey)).address;
+    await mintTo(connection, user, escrow_mint, escrow_mint_ata, user, 1);
+
+    await createMetadataAccountV3(umi, {
+      mint: publicKey(escrow_mint),
+      mintAuthority: creator,
+      data: {
+        name: "Squire",
+        symbol: "SQR",
+        uri: "https://arweave.net/42",
+        sellerFeeBasisPoints: 0,
+        creators: null,
+        collection: { verified: false, key: collectionMint.publicKey },
+        uses: null,
+      },
+      isMutable: true,
+      collectionDetails: null,
+    }).sendAndConfirm(umi);
+
+    const [escrow_metadata] = findMetadataPda(umi, { mint: publicKey(escrow_mint) });
+    const [escrow_edition] = findMasterEditionPda(umi, { mint: publicKey(escrow_mint) });
+
+    await verifySizedCollectionItem(umi, {
+      metadata: escrow_metadata,
+      collectionAuthority: creator,
+      collectionMint: collectionMint.publicKey,
+      collection: findMetadataPda(umi, { mint: collectionMint.publicKey }),
+      collectionMasterEditionAccount: findMasterEditionPda(umi, { mint: collectionMint.publicKey }),
+    }).sendAndConfirm(umi);
+
+    const escrow_seed = new BN(randomBytes(8));
+    const escrow_stake_account = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), config.toBuffer(), escrow_mint.toBuffer(), escrow_seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+    const escrow_vault_ata = getAssociatedTokenAddressSync(escrow_mint, escrow_stake_account, true);
+
+    const metadata_program = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+    await program.methods
+    .stakeNft(escrow_seed, true, new anchor.BN(60))
+    .accountsStrict({
+      user: user.publicKey,
+      mint: escrow_mint,
+      collectionMint: collectionMint.publicKey,
+      mintAta: escrow_mint_ata,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      metadata: escrow_metadata,
+      masterEdition: escrow_edition,
+      vaultAta: escrow_vault_ata,
+      tokenRecord: null,
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
+      stakeAccount: escrow_stake_account,
+      config: config,
+      lockTiers: lock_tiers,
+      nftPool: nft_pool,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      metadataProgram: metadata_program,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+
+    assert((await connection.getTokenAccountBalance(escrow_vault_ata)).value.amount === "1", "NFT not escrowed");
+
+    // the attacker brings a complete set of their own accounts and points them at the escrowed stake
+    const attacker = Keypair.generate();
+    await connection.requestAirdrop(attacker.publicKey, 10 * LAMPORTS_PER_SOL).then(confirmTx);
+
+    const attacker_account = PublicKey.findProgramAddressSync(
+      [Buffer.from("user"), attacker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+    .initializeUser()
+    .accountsStrict({
+      user: attacker.publicKey,
+      userAccount: attacker_account,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([attacker])
+    .rpc();
+
+    const attacker_mint_ata = (await getOrCreateAssociatedTokenAccount(connection, attacker, escrow_mint, attacker.publicKey)).address;
+    const attacker_reward_ata = (await getOrCreateAssociatedTokenAccount(connection, attacker, reward_mint, attacker.publicKey)).address;
+
+    let error = "";
+    try {
+      await program.methods
+      .unstakeNft()
+      .accountsStrict({
+        user: attacker.publicKey,
+        mint: escrow_mint,
+        collectionMint: collectionMint.publicKey,
+        mintAta: attacker_mint_ata,
+        rewardMint: reward_mint,
+        userRewardAta: attacker_reward_ata,
+        rewardVault: null,
+        metadata: escrow_metadata,
+        masterEdition: escrow_edition,
+        vaultAta: escrow_vault_ata,
+        tokenRecord: null,
+        authorizationRules: null,
+        authorizationRulesProgram: null,
+        sysvarInstructions: null,
+        stakeAccount: escrow_stake_account,
+        config: config,
+        nftPool: nft_pool,
+        userAccount: attacker_account,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        metadataProgram: metadata_program,
+        systemProgram: anchor.web3.SystemProgram.programId
+      })
+      .signers([attacker])
+      .rpc();
+    } catch (err) {
+      error = err.toString();
+    }
+
+    assert(error.includes("InvalidStakeOwner"), "Another user unstaked the escrowed NFT");
+    assert((await connection.getTokenAccountBalance(escrow_vault_ata)).value.amount === "1", "Escrowed NFT left the vault");
+  })
+
+  it("stake and unstake an nft batch through a lookup table", async () => {
+    const batch_seed = new BN(randomBytes(8));
+
+    const [batch_stake_account] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), config.toBuffer(), new PublicKey(nftMint.publicKey).toBuffer(), batch_seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // anyone can send lamports to the stake PDA before it is created, that must not block the stake
+    const prefund_sig = await connection.sendTransaction(
+      new Transaction().add(anchor.web3.SystemProgram.transfer({ fromPubkey: user.publicKey, toPubkey: batch_stake_account, lamports: 1_000_000 })),
+      [user]
+    );
+    await confirmTx(prefund_sig);
+
+    const [metadata] = findMetadataPda(umi, { mint: nftMint.publicKey });
+    const [masterEditon] = findMasterEditionPda(umi, {
+      mint: nftMint.publicKey,
+    });
+
+    // [mint, token_account, metadata, edition, stake_account] for every NFT of the batch
+    const nft_accounts = batchNftAccounts(
+      new PublicKey(nftMint.publicKey),
+      nft_mint_ata,
+      new PublicKey(metadata),
+      new PublicKey(masterEditon),
+      batch_stake_account
+    );
+
+    const metadata_program = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+    const lookup_table = await createBatchLookupTable(connection, user, [
+      config,
+      lock_tiers,
+      nft_pool,
+      user_account,
+      reward_mint,
+      user_reward_ata,
+      collection,
+      TOKEN_PROGRAM_ID,
+      metadata_program,
+      anchor.web3.SystemProgram.programId,
+      ...nft_accounts.map((account) => account.pubkey),
+    ]);
+
+    const stake_ix = await program.methods
+    .stakeNftBatch([batch_seed], true, new anchor.BN(60))
+    .accountsStrict({
+      user: user.publicKey,
+      collectionMint: collection,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      config: config,
+      lockTiers: lock_tiers,
+      nftPool: nft_pool,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      metadataProgram: metadata_program,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .remainingAccounts(nft_accounts)
+    .instruction()
+
+    let tx = await sendV0(connection, [stake_ix], user, [lookup_table]);
+    console.log("tx: ", tx);
+
+    let user_account_pda = await program.account.userAccount.fetch(user_account);
+    assert(user_account_pda.nftStakedAmount.toNumber() === 1, "Batch not staked");
+
+    await sleep(65 * 1000);
+
+    const unstake_ix = await program.methods
+    .unstakeNftBatch()
+    .accountsStrict({
+      user: user.publicKey,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      config: config,
+      nftPool: nft_pool,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      metadataProgram: metadata_program,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .remainingAccounts(nft_accounts)
+    .instruction()
+
+    tx = await sendV0(connection, [unstake_ix], user, [lookup_table]);
+    console.log("tx: ", tx);
+
+    user_account_pda = await program.account.userAccount.fetch(user_account);
+    assert(user_account_pda.nftStakedAmount.toNumber() === 0, "Batch not unstaked");
+
+    const closed = await connection.getAccountInfo(batch_stake_account);
+    assert(closed === null, "Stake account not closed");
+  })
+
   let seed1: any;
   let stake_account_sol: PublicKey;
   it("stake sol", async () => {
@@ -414,9 +883,11 @@ describe("stacking_program", () => {
       user: user.publicKey,
       rewardMint: reward_mint,
       userRewardAta: user_reward_ata,
+      rewardVault: null,
       stakeAccount: stake_account_sol,
       config: config,
-      // vault: vault,
+      lockTiers: lock_tiers,
+      vault: vault,
       userAccount: user_account,
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
@@ -426,9 +897,10 @@ describe("stacking_program", () => {
 
   console.log("tx:", tx);
 
-  // const vault_balance = await connection.getBalance(vault);
-  // console.log("vault_balance:", vault_balance);
-  // assert(vault_balance === 1 * LAMPORTS_PER_SOL, "Vault Balance not equal");
+  const vault_balance = await connection.getBalance(vault);
+  console.log("vault_balance:", vault_balance);
+  const reserve = await connection.getMinimumBalanceForRentExemption(0);
+  assert(vault_balance === 1 * LAMPORTS_PER_SOL + reserve, "Vault Balance not equal");
   const user_balane_final = await connection.getBalance(user.publicKey);
   console.log("Balance b4 staking tx :", user_balane_final);
 
@@ -542,6 +1014,34 @@ describe("stacking_program", () => {
 
   // })
 
+  it("increase sol stake", async () => {
+    const tx = await program.methods
+    .increaseStakeSol(new anchor.BN(500_000_000), null)
+    .accountsStrict({
+      user: user.publicKey,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      stakeAccount: stake_account_sol,
+      vault: PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), stake_account_sol.toBuffer()],
+        program.programId
+      )[0],
+      config: config,
+      lockTiers: lock_tiers,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([user])
+    .rpc();
+
+    console.log("tx:", tx);
+
+    const stake_account_sol_pda = await program.account.stakeAccount.fetch(stake_account_sol);
+    assert(stake_account_sol_pda.stakedAmt.toNumber() === 1_500_000_000, "Position not increased");
+  });
+
   it("unstake sol", async () => {
   console.log("seed", seed1.toString());
 
@@ -564,15 +1064,20 @@ describe("stacking_program", () => {
   console.log("Balance b4 staking tx :", user_balane_init);
   await sleep(65 * 1000);
   const tx = await program.methods
-    .unstakeSol()
+    .unstakeSol(new anchor.BN(1_500_000_000))
     .accountsStrict({
       user: user.publicKey,
       rewardMint: reward_mint,
       userRewardAta: user_reward_ata,
+      rewardVault: null,
       stakeAccount: stake_account_sol,
       config: config,
-      // vault: vault,
+      vault: vault,
       userAccount: user_account,
+      nativeStake: null,
+      clock: null,
+      stakeHistory: null,
+      stakeProgram: null,
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
     })
@@ -581,9 +1086,9 @@ describe("stacking_program", () => {
 
   console.log("tx:", tx);
 
-  // const vault_balance = await connection.getBalance(vault);
-  // console.log("vault_balance:", vault_balance);
-  // assert(vault_balance === 0, "Vault Balance not equal");
+  const vault_balance = await connection.getBalance(vault);
+  console.log("vault_balance:", vault_balance);
+  assert(vault_balance === 0, "Vault Balance not equal");
 
     const user_balane_final = await connection.getBalance(user.publicKey);
   console.log("Balance b4 staking tx :", user_balane_final);
@@ -594,10 +1099,132 @@ describe("stacking_program", () => {
   console.log("rewards_received:", reward_received?.value?.uiAmount);
 });
 
+  it("emergency unstake sol before the lock ends", async () => {
+    const seed2 = new BN(randomBytes(8));
+
+    const stake_account_early = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("stake"),
+        config.toBuffer(),
+        user.publicKey.toBuffer(),
+        seed2.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    const vault_early = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), stake_account_early.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+    .stakeSol(seed2, new anchor.BN(1_000_000_000), true, new anchor.BN(60))
+    .accountsStrict({
+      user: user.publicKey,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      stakeAccount: stake_account_early,
+      vault: vault_early,
+      config: config,
+      lockTiers: lock_tiers,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([user])
+    .rpc();
+
+    const treasury_balance_init = await connection.getBalance(treasury);
+
+    const tx = await program.methods
+    .emergencyUnstakeSol()
+    .accountsStrict({
+      user: user.publicKey,
+      stakeAccount: stake_account_early,
+      vault: vault_early,
+      config: config,
+      treasury: treasury,
+      userAccount: user_account,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([user])
+    .rpc();
+
+    console.log("tx:", tx);
+
+    const treasury_balance_final = await connection.getBalance(treasury);
+    // 10% of the principal is withheld for leaving inside the lock
+    assert(treasury_balance_final - treasury_balance_init === 100_000_000, "Penalty not sent to treasury");
+  });
+
+  it("withdraw sol penalties from the treasury", async () => {
+    let rejected = false;
+    try {
+      await program.methods
+      .withdrawTreasurySol(new anchor.BN(100_000_000))
+      .accountsStrict({
+        admin: user.publicKey,
+        config: config,
+        treasury: treasury,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+    } catch (err) {
+      rejected = true;
+    }
+    assert(rejected, "Non admin withdrew from the treasury");
+
+    const treasury_balance_init = await connection.getBalance(treasury);
+
+    const tx = await program.methods
+    .withdrawTreasurySol(new anchor.BN(100_000_000))
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+      treasury: treasury,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([admin])
+    .rpc();
+
+    console.log("tx:", tx);
+
+    const treasury_balance_final = await connection.getBalance(treasury);
+    assert(treasury_balance_init - treasury_balance_final === 100_000_000, "Penalty not withdrawn from treasury");
+  });
+
   let mint_ata: PublicKey;
   let vault_ata: PublicKey;
   let stake_account_spl: PublicKey;
   let seed3: any;
+  let spl_pool: PublicKey;
+  it("create spl pool", async () => {
+
+    spl_pool = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), mint.toBuffer()],
+      program.programId
+    )[0];
+
+    const tx = await program.methods
+    .createSplPool(new anchor.BN(1), new anchor.BN(1_000_000), new anchor.BN(100_000_000))
+    .accountsStrict({
+      admin: admin.publicKey,
+      mint: mint,
+      config: config,
+      splPool: spl_pool,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([admin])
+    .rpc()
+
+    console.log("tx :", tx);
+
+    const spl_pool_pda = await program.account.splPool.fetch(spl_pool);
+    assert(spl_pool_pda.enabled, "Pool not enabled");
+  })
+
   it("stake spl token", async () => {
 
     seed3 = new BN(randomBytes(8));
@@ -648,6 +1275,8 @@ describe("stacking_program", () => {
     )
 
 
+    const reward_before = await connection.getTokenAccountBalance(user_reward_ata);
+
     const tx = await program.methods
     .stakeSpl(seed3, new anchor.BN(10_000_000), true, new anchor.BN(60))
     .accountsStrict({
@@ -656,11 +1285,15 @@ describe("stacking_program", () => {
       mintAta: mint_ata,
       rewardMint: reward_mint,
       userRewardAta: user_reward_ata,
+      rewardVault: null,
       stakeAccount: stake_account_spl,
       config: config,
+      lockTiers: lock_tiers,
+      splPool: spl_pool,
       vaultAta: vault_ata,
       userAccount: user_account,
       tokenProgram: TOKEN_PROGRAM_ID,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId
     })
@@ -675,10 +1308,43 @@ describe("stacking_program", () => {
 
 
     const reward_recieved = await connection.getTokenAccountBalance(user_reward_ata);
+    // the flat one time SPL_STAKE_BONUS, independent of the amount staked
+    assert(Number(reward_recieved.value.amount) - Number(reward_before.value.amount) === 100_000_000, "Stake bonus not paid");
 
     console.log("rewards_received :", reward_recieved?.value?.uiAmount);
   })
 
+  it("claim rewards on spl stake", async () => {
+
+    await sleep(5 * 1000);
+
+    const reward_before = await connection.getTokenAccountBalance(user_reward_ata);
+
+    const tx = await program.methods
+    .claimRewards()
+    .accountsStrict({
+      user: user.publicKey,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      stakeAccount: stake_account_spl,
+      config: config,
+      splPool: spl_pool,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc()
+
+    console.log("tx :", tx);
+
+    const reward_after = await connection.getTokenAccountBalance(user_reward_ata);
+    assert(BigInt(reward_after.value.amount) > BigInt(reward_before.value.amount), "No rewards claimed");
+
+    const stake_account_pda = await program.account.stakeAccount.fetch(stake_account_spl);
+    assert(stake_account_pda.lastClaimedAt.gt(stake_account_pda.stakedAt), "Checkpoint not advanced");
+  })
+
   it("unstake spl token", async () => {
 
     // mint_ata = (await getOrCreateAssociatedTokenAccount(
@@ -727,25 +1393,57 @@ describe("stacking_program", () => {
 
     await sleep(65 * 1000);
 
+    const partial_tx = await program.methods
+    .unstakeSpl(new anchor.BN(4_000_000))
+    .accountsStrict({
+      user: user.publicKey,
+      mint: mint,
+      mintAta: mint_ata,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      stakeAccount: stake_account_spl,
+      config: config,
+      splPool: spl_pool,
+      vaultAta: vault_ata,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc()
+
+    console.log("partial tx :", partial_tx);
+
+    const stake_account_spl_pda = await program.account.stakeAccount.fetch(stake_account_spl);
+    assert(stake_account_spl_pda.stakedAmt.toNumber() === 6_000_000, "Position not reduced");
+
     const tx = await program.methods
-    .unstakeSpl()
+    .unstakeSpl(new anchor.BN(6_000_000))
     .accountsStrict({
       user: user.publicKey,
       mint: mint,
       mintAta: mint_ata,
       rewardMint: reward_mint,
       userRewardAta: user_reward_ata,
+      rewardVault: null,
       stakeAccount: stake_account_spl,
       config: config,
+      splPool: spl_pool,
       vaultAta: vault_ata,
       userAccount: user_account,
       tokenProgram: TOKEN_PROGRAM_ID,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId
     })
     .signers([user])
     .rpc()
 
+    assert((await connection.getAccountInfo(stake_account_spl)) === null, "Stake account not closed");
+
     console.log("tx :", tx);
 
     const reward_recieved = await connection.getTokenAccountBalance(user_reward_ata);
@@ -753,4 +1451,233 @@ describe("stacking_program", () => {
     console.log("rewards_received :", reward_recieved?.value?.uiAmount);
   })
 
+  it("withdraw spl penalties from the treasury", async () => {
+    const treasury_ata = (await getOrCreateAssociatedTokenAccount(connection, user, mint, treasury, true)).address;
+    const admin_ata = (await getOrCreateAssociatedTokenAccount(connection, admin, mint, admin.publicKey)).address;
+
+    // stands in for penalties collected by emergency_unstake_spl
+    await mintTo(connection, user, mint, treasury_ata, user, 1_000_000);
+
+    const tx = await program.methods
+    .withdrawTreasurySpl(new anchor.BN(1_000_000))
+    .accountsStrict({
+      admin: admin.publicKey,
+      config: config,
+      treasury: treasury,
+      mint: mint,
+      treasuryAta: treasury_ata,
+      adminAta: admin_ata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([admin])
+    .rpc();
+
+    console.log("tx :", tx);
+
+    const treasury_balance = await connection.getTokenAccountBalance(treasury_ata);
+    const admin_balance = await connection.getTokenAccountBalance(admin_ata);
+    assert(treasury_balance.value.amount === "0", "Treasury not emptied");
+    assert(admin_balance.value.amount === "1000000", "Penalty not received by admin");
+  })
+
+  it("stake and fully unstake a token-2022 transfer fee mint", async () => {
+    const fee_mint = Keypair.generate();
+    const mint_len = getMintLen([ExtensionType.TransferFeeConfig]);
+
+    // 1% fee on every transfer, withheld in the receiving account
+    const create_mint_tx = new Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: user.publicKey,
+        newAccountPubkey: fee_mint.publicKey,
+        space: mint_len,
+        lamports: await connection.getMinimumBalanceForRentExemption(mint_len),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeTransferFeeConfigInstruction(fee_mint.publicKey, user.publicKey, user.publicKey, 100, BigInt(1_000_000_000), TOKEN_2022_PROGRAM_ID),
+      createInitializeMintInstruction(fee_mint.publicKey, 6, user.publicKey, null, TOKEN_2022_PROGRAM_ID),
+    );
+    await provider.sendAndConfirm(create_mint_tx, [user, fee_mint]);
+
+    const fee_mint_ata = (await getOrCreateAssociatedTokenAccount(
+      connection,
+      user,
+      fee_mint.publicKey,
+      user.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID,
+    )).address;
+
+    await mintTo(connection, user, fee_mint.publicKey, fee_mint_ata, user, 100_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    const fee_pool = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), fee_mint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+    .createSplPool(new anchor.BN(1), new anchor.BN(1_000_000), new anchor.BN(100_000_000))
+    .accountsStrict({
+      admin: admin.publicKey,
+      mint: fee_mint.publicKey,
+      config: config,
+      splPool: fee_pool,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([admin])
+    .rpc();
+
+    const seed5 = new BN(randomBytes(8));
+
+    const stake_account_fee = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), config.toBuffer(), user.publicKey.toBuffer(), fee_mint.publicKey.toBuffer(), seed5.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+    const fee_vault_ata = getAssociatedTokenAddressSync(fee_mint.publicKey, stake_account_fee, true, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+    .stakeSpl(seed5, new anchor.BN(10_000_000), true, new anchor.BN(60))
+    .accountsStrict({
+      user: user.publicKey,
+      mint: fee_mint.publicKey,
+      mintAta: fee_mint_ata,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      stakeAccount: stake_account_fee,
+      config: config,
+      lockTiers: lock_tiers,
+      splPool: fee_pool,
+      vaultAta: fee_vault_ata,
+      userAccount: user_account,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+
+    // only the net amount is credited, the fee stays withheld in the vault
+    const stake_account_fee_pda = await program.account.stakeAccount.fetch(stake_account_fee);
+    assert(stake_account_fee_pda.stakedAmt.toNumber() === 9_900_000, "Net amount not credited");
+
+    await sleep(65 * 1000);
+
+    const tx = await program.methods
+    .unstakeSpl(stake_account_fee_pda.stakedAmt)
+    .accountsStrict({
+      user: user.publicKey,
+      mint: fee_mint.publicKey,
+      mintAta: fee_mint_ata,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      stakeAccount: stake_account_fee,
+      config: config,
+      splPool: fee_pool,
+      vaultAta: fee_vault_ata,
+      userAccount: user_account,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+
+    console.log("tx :", tx);
+
+    assert((await connection.getAccountInfo(fee_vault_ata)) === null, "Vault with withheld fees not closed");
+    assert((await connection.getAccountInfo(stake_account_fee)) === null, "Stake account not closed");
+  })
+
+  it("compound rewards on a reward mint stake", async () => {
+    const seed4 = new BN(randomBytes(8));
+
+    const reward_pool = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), reward_mint.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+    .createSplPool(new anchor.BN(1), new anchor.BN(1), new anchor.BN(1_000_000_000_000))
+    .accountsStrict({
+      admin: admin.publicKey,
+      mint: reward_mint,
+      config: config,
+      splPool: reward_pool,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([admin])
+    .rpc();
+
+    const stake_account_compound = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), config.toBuffer(), user.publicKey.toBuffer(), reward_mint.toBuffer(), seed4.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+    const compound_vault_ata = getAssociatedTokenAddressSync(reward_mint, stake_account_compound, true);
+
+    await program.methods
+    .stakeSpl(seed4, new anchor.BN(1_000_000), false, new anchor.BN(60))
+    .accountsStrict({
+      user: user.publicKey,
+      mint: reward_mint,
+      mintAta: user_reward_ata,
+      rewardMint: reward_mint,
+      userRewardAta: user_reward_ata,
+      rewardVault: null,
+      stakeAccount: stake_account_compound,
+      config: config,
+      lockTiers: lock_tiers,
+      splPool: reward_pool,
+      vaultAta: compound_vault_ata,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers([user])
+    .rpc();
+
+    await program.methods
+    .setCompounding(true)
+    .accountsStrict({
+      user: user.publicKey,
+      stakeAccount: stake_account_compound,
+      config: config,
+    })
+    .signers([user])
+    .rpc();
+
+    await sleep(2 * 1000);
+
+    const tx = await program.methods
+    .compound()
+    .accountsStrict({
+      signer: admin.publicKey,
+      rewardMint: reward_mint,
+      rewardVault: null,
+      stakeAccount: stake_account_compound,
+      config: config,
+      splPool: reward_pool,
+      vaultAta: compound_vault_ata,
+      userAccount: user_account,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([admin])
+    .rpc();
+
+    console.log("tx :", tx);
+
+    const stake_account_compound_pda = await program.account.stakeAccount.fetch(stake_account_compound);
+    const vault_balance = await connection.getTokenAccountBalance(compound_vault_ata);
+    assert(stake_account_compound_pda.stakedAmt.toNumber() > 1_000_000, "Rewards not compounded");
+    assert(vault_balance.value.amount === stake_account_compound_pda.stakedAmt.toString(), "Vault and position out of sync");
+  })
+
 });